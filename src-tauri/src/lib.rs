@@ -1,184 +1,3472 @@
-use std::process::Command;
+use futures::future::join_all;
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Semaphore;
+use tracing::{debug, instrument, warn};
+use tracing_subscriber::EnvFilter;
+
+// Ping timeout applied when the caller doesn't specify one.
+const DEFAULT_PING_TIMEOUT_MS: u32 = 1000;
+
+// Extra attempts the batch checks and host monitors make before reporting a host
+// offline, so one dropped echo over Wi-Fi doesn't flip the status dot.
+const BATCH_PING_RETRIES: u32 = 2;
+const PING_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+// Largest ping payload accepted by every supported platform (Windows caps at 65500).
+const MAX_PING_SIZE_BYTES: u32 = 65500;
+
+// How long ssh waits for the TCP connection before giving up.
+const DEFAULT_SSH_CONNECT_TIMEOUT_SECS: u32 = 10;
+
+// ssh's own default port; left off the command line so ~/.ssh/config can override it.
+const DEFAULT_SSH_PORT: u16 = 22;
+
+// Exit code ssh itself uses for its own errors; anything else came from the remote command.
+const SSH_ERROR_EXIT: i32 = 255;
+
+// Remote commands behind exec_shutdown_command / exec_reboot_command.
+const SHUTDOWN_COMMAND: &str = "sudo shutdown -h now";
+const REBOOT_COMMAND: &str = "sudo reboot";
+
+// How long open_ssh_terminal waits for the terminal process to spawn.
+const TERMINAL_LAUNCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Upper bound for TerminalOptions.tab_delay_ms; the delay runs inside the launch timeout.
+const MAX_TAB_DELAY_MS: u32 = 2000;
+
+// How often exec_shutdown_command pings the host while waiting for it to go down.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+// Routine run by restart_ros when the caller doesn't provide one.
+const DEFAULT_ROS_RESTART_COMMAND: &str = "ros2_start --restart";
+
+// Routine safe_shutdown runs to stop nodes and close bags before powering off.
+const DEFAULT_ROS_STOP_COMMAND: &str = "ros2_start --stop";
+
+// Launch log read by fetch_ros_logs when no path is given.
+const DEFAULT_ROS_LOG_PATH: &str = "~/.ros/log/latest/launch.log";
+
+// Upper bound on hosts probed at once by batch commands.
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+// Files under the app config dir holding AppSettings and the per-host presets.
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const HOST_PRESETS_FILE_NAME: &str = "host_presets.json";
+
+// Hard ceiling on read_remote_file, whatever max_bytes the caller asks for.
+const MAX_REMOTE_FILE_BYTES: usize = 1024 * 1024;
+
+// Bumped whenever the layout of the diagnostics bundle changes.
+const DIAGNOSTICS_SCHEMA_VERSION: u32 = 1;
+
+// Printed between /proc/loadavg and /proc/meminfo by get_system_vitals.
+const VITALS_SENTINEL: &str = "----KYUBIC-VITALS----";
 
 // =========================================
 // 1. Types & Enums
 // =========================================
 
 // Defines how the terminal window should be opened.
+// True split panes only exist in Windows Terminal; gnome-terminal, Konsole and
+// macOS open a new tab instead, and xterm/Alacritty a new window.
+#[derive(Clone, Copy, Debug)]
 enum WindowMode {
     Tab,       // Open as a new tab in the existing terminal (if supported)
     NewWindow, // Force a new independent window
+    SplitPane, // Split the current window (best effort, see above)
+}
+
+// Terminal emulators that can be driven on Linux, in fallback order.
+// Override the choice with the `terminal` setting or the KYUBIC_TERMINAL environment variable.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug)]
+enum TerminalEmulator {
+    GnomeTerminal,
+    Konsole,
+    Xterm,     // No tab support, always opens a new window
+    Alacritty, // No tab support, always opens a new window
+}
+
+// Terminal applications that can be scripted on macOS.
+// Override the choice with the `terminal` setting or KYUBIC_TERMINAL ("terminal" / "iterm2").
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy, Debug)]
+enum MacTerminalApp {
+    Terminal, // Terminal.app (default)
+    ITerm2,   // Used automatically when iTerm2 is already running
+}
+
+// How ssh treats the robot's host key, set per call through SshOptions.
+// Reimaged robots get new host keys, which otherwise leaves operators at the
+// yes/no prompt or aborts ssh with a mismatch. Loosening this trades away
+// protection against a spoofed host on the tether.
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+enum HostKeyPolicy {
+    #[default]
+    Strict, // ssh's default checking: unknown hosts prompt, changed keys abort
+    AcceptNew, // Trust unknown hosts on first use and accept server key updates
+    Off,       // Accept any host key and record nothing
+}
+
+// Result of a single reachability probe.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum PingState {
+    Online,      // Host answered the echo request
+    Offline,     // Ping ran but got no reply
+    ToolMissing, // The ping executable could not be found
+    Unknown,     // The probe itself failed (task panicked), so nothing is known
+}
+
+// Summary of a multi-echo ping run.
+#[derive(Serialize, Debug)]
+struct LinkQuality {
+    sent: u32,
+    received: u32,
+    loss_percent: f64,
+    avg_rtt_ms: Option<f64>, // None when no reply was received
+}
+
+// Packet options for the diagnostic pings (ping_verbose, measure_link_quality),
+// used to probe the tether's MTU. Passed as a camelCase object.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, rename_all = "camelCase")]
+struct PingOptions {
+    size_bytes: Option<u32>, // Payload size: -s on Unix, -l on Windows
+    dont_fragment: bool,     // Set DF: -M do on Linux, -D on macOS, -f on Windows
+}
+
+// Connection options shared by every ssh invocation.
+// Passed from the frontend as a single camelCase object; every field is optional.
+// The local-terminal branch of open_ssh_terminal ignores all of them.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, rename_all = "camelCase")]
+struct SshOptions {
+    port: Option<u16>,                 // -p <port>, omitted to use the ssh default
+    username: Option<String>,          // Prepended as user@hostname
+    identity_file: Option<String>, // -i <path>, `~/` expanded and checked to exist before launching
+    connect_timeout_secs: Option<u32>, // -o ConnectTimeout=<secs>, defaults to 10s
+    multiplex: bool,               // Reuse a ControlMaster connection (ignored by Windows ssh)
+    jump_host: Option<String>,     // -J <jump_host> bastion, may itself be user@host:port
+    wsl: bool,                     // Windows only: run ssh inside WSL to use its keys and config
+    wsl_distro: Option<String>,    // WSL distribution for wsl.exe -d, default distro when unset
+    extra_ssh_opts: Vec<String>, // Extra flag/value pairs placed before the host, e.g. -o Compression=yes
+    host_key_policy: HostKeyPolicy, // -o StrictHostKeyChecking/UserKnownHostsFile, Strict by default
+}
+
+// Presentation options for the spawned terminal, passed as a camelCase object.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, rename_all = "camelCase")]
+struct TerminalOptions {
+    profile: Option<String>,   // Windows Terminal profile (-p), appearance only
+    title: Option<String>,     // Window/tab title; open_ssh_terminal defaults it to the hostname
+    split_pane: bool,          // open_ssh_terminal opens a split pane instead of a tab
+    tab_delay_ms: Option<u32>, // macOS Terminal.app: wait after Cmd-T, default 200ms, max 2000ms
+}
+
+// Captured result of a non-interactive remote command.
+#[derive(Serialize, Debug)]
+struct RemoteResult {
+    stdout: String, // ANSI escape sequences removed
+    stderr: String,
+    exit_code: Option<i32>, // None when ssh was terminated by a signal
+}
+
+// Usage of a single mounted filesystem on a remote host.
+#[derive(Serialize, Debug)]
+struct DiskInfo {
+    mount: String,
+    total_bytes: u64,
+    used_bytes: u64,
+    available_bytes: u64,
+}
+
+// Load average and memory snapshot of a remote host.
+#[derive(Serialize, Debug)]
+struct Vitals {
+    load_1m: f64,
+    load_5m: f64,
+    load_15m: f64,
+    mem_total_kb: u64,
+    mem_available_kb: u64,
+}
+
+// User tunables persisted by save_settings, passed as a camelCase object.
+// Fields missing from an older settings file fall back to their defaults.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default, rename_all = "camelCase")]
+struct AppSettings {
+    ssh_port: u16,                 // Port used when a host doesn't specify one
+    ssh_connect_timeout_secs: u32, // ssh -o ConnectTimeout
+    ping_timeout_ms: u32,          // Timeout for status pings
+    terminal: Option<String>,      // Preferred emulator (Linux/macOS), KYUBIC_TERMINAL wins
+    max_concurrency: usize,        // Cap on hosts probed in parallel
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            ssh_port: DEFAULT_SSH_PORT,
+            ssh_connect_timeout_secs: DEFAULT_SSH_CONNECT_TIMEOUT_SECS,
+            ping_timeout_ms: DEFAULT_PING_TIMEOUT_MS,
+            terminal: None,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+}
+
+// Per-host defaults for open_ssh_terminal, saved by save_host_preset.
+// Any field left unset here falls through to the command's own default.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, rename_all = "camelCase")]
+struct HostPreset {
+    run_ros: Option<bool>,
+    remote_command: Option<String>,
+    port: Option<u16>,
+    username: Option<String>,
+}
+
+// One machine from the hosts config file.
+#[derive(Serialize, Deserialize, Debug)]
+struct HostEntry {
+    name: String,
+    hostname: String,
+    ip: String,
+    #[serde(default)]
+    run_ros_default: bool,
+}
+
+// Layout of the hosts config file, shared by the TOML and JSON formats.
+#[derive(Deserialize)]
+struct HostsConfig {
+    hosts: Vec<HostEntry>,
+}
+
+// Payload of the `ros-topic-echo` event, one per echoed message.
+#[derive(Serialize, Clone, Debug)]
+struct TopicEchoEvent {
+    topic: String,
+    message: String,
+}
+
+// One network interface of the operator machine, as reported by list_local_interfaces.
+// Addresses are in CIDR notation so the UI can compare subnets with the robot's.
+#[derive(Serialize, Debug)]
+struct IfaceInfo {
+    name: String,
+    ipv4: Vec<String>,
+    ipv6: Vec<String>,
+    is_up: bool,
+}
+
+// Contents of the file written by generate_diagnostics_bundle, attached to bug reports.
+// Sections that couldn't be collected are left empty and explained in `errors`.
+#[derive(Serialize, Debug)]
+struct DiagnosticsBundle {
+    schema_version: u32,
+    generated_at: f64, // Unix time in seconds
+    hostname: String,
+    ping: PingResult,
+    prerequisites: Vec<PrereqStatus>,
+    interfaces: Vec<IfaceInfo>,
+    settings: Option<AppSettings>,
+    errors: Vec<String>,
+}
+
+// Whether a local tool the app shells out to is installed.
+#[derive(Serialize, Debug)]
+struct PrereqStatus {
+    name: String,
+    available: bool,
+    path: Option<String>,
+}
+
+// Payload of the `batch-complete` event emitted by check_batch_latency when given a scan id.
+#[derive(Serialize, Clone, Debug)]
+struct BatchCompleteEvent {
+    scan_id: String,
+    results: HashMap<String, HostLatency>,
+}
+
+// Per-host entry of check_batch_latency, serialized as `{ state, latency_ms }`.
+#[derive(Serialize, Clone, Debug)]
+struct HostLatency {
+    #[serde(flatten)]
+    state: PingState,
+    latency_ms: Option<f64>, // None unless online, and even then when ping printed no time
+}
+
+impl HostLatency {
+    fn without_time(state: PingState) -> Self {
+        HostLatency {
+            state,
+            latency_ms: None,
+        }
+    }
+}
+
+// Per-host entry of check_batch_detailed, in the same order as the input targets.
+#[derive(Serialize, Debug)]
+struct HostStatus {
+    target: String,
+    online: bool,
+    latency_ms: Option<f64>, // None when offline, or online but ping printed no time
+    resolved_ip: Option<String>, // None when the target didn't resolve
+}
+
+// What scan_subnet would cover for a CIDR, as reported by validate_cidr.
+#[derive(Serialize, Debug)]
+struct CidrInfo {
+    network: String,
+    prefix_len: u32,
+    host_count: u32,
+}
+
+// Result of ping_with_resolution; `resolved_ip` is None when resolution failed.
+#[derive(Serialize, Debug)]
+struct PingResult {
+    resolved_ip: Option<String>,
+    reachable: bool,
+    error: Option<String>,
+}
+
+// Payload of the `subnet-scan-progress` event, sent after each address is pinged.
+#[derive(Serialize, Clone, Debug)]
+struct ScanProgressEvent {
+    scanned: usize,
+    total: usize,
+}
+
+// Payload of the `host-shutdown-confirmed` event, sent once per confirmed shutdown.
+// `confirmed` is false when the host still answered at the end of the timeout.
+#[derive(Serialize, Clone, Debug)]
+struct ShutdownConfirmedEvent {
+    hostname: String,
+    confirmed: bool,
+    elapsed_secs: u64,
+}
+
+// Payload of the `host-state-changed` event, sent by a host monitor when the state flips.
+#[derive(Serialize, Clone, Debug)]
+struct HostStateChangedEvent {
+    monitor_id: String,
+    target: String,
+    #[serde(flatten)]
+    state: PingState,
+}
+
+// Error returned by every fallible command.
+// Serialized as `{ kind, message }` so the frontend can branch on `kind`
+// and still show `message` as-is.
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+enum AppError {
+    InvalidInput(String),   // Rejected argument (zero count, bad path, ...)
+    NotFound(String),       // Local file or remote log that doesn't exist
+    ToolMissing(String),    // ping/ssh binary not installed
+    SpawnFailed(String),    // Local process could not be started or read
+    ReadFailed(String),     // Local file exists but could not be read
+    WriteFailed(String),    // Local file could not be written
+    SshFailed(String),      // The remote command exited non-zero
+    AuthFailed(String),     // ssh connected but the key/password was rejected
+    Unreachable(String),    // ssh couldn't connect: refused, no route, unresolvable
+    Timeout(String),        // ssh or the terminal launcher didn't respond in time
+    ResolveFailed(String),  // Hostname didn't resolve (DNS/mDNS)
+    ParseFailed(String),    // Output or file wasn't in the expected format
+    TerminalLaunch(String), // No terminal could be opened
+    OpenFailed(String),     // The file manager could not be opened
+    EventEmit(String),      // Streaming an event to the frontend failed
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    PermissionDenied(String), // macOS refused Automation/Accessibility access
+}
+
+impl AppError {
+    /// Classifies a failure to start `tool`; a missing binary gets its own kind.
+    fn spawn(tool: &str, e: std::io::Error) -> Self {
+        if e.kind() == ErrorKind::NotFound {
+            AppError::ToolMissing(format!("{} is not installed or not on PATH", tool))
+        } else {
+            AppError::SpawnFailed(format!("Failed to run {}: {}", tool, e))
+        }
+    }
+
+    /// Wraps a failed ssh run. ssh itself exits with 255, so that exit is a connection
+    /// failure (Timeout when it timed out); any other code came from the remote command.
+    fn ssh(context: String, output: &Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = format!("{}: {}", context, stderr.trim());
+        if stderr.contains("timed out") {
+            AppError::Timeout(message)
+        } else if output.status.code() == Some(SSH_ERROR_EXIT) {
+            AppError::Unreachable(message)
+        } else {
+            AppError::SshFailed(message)
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::InvalidInput(message)
+            | AppError::NotFound(message)
+            | AppError::ToolMissing(message)
+            | AppError::SpawnFailed(message)
+            | AppError::ReadFailed(message)
+            | AppError::WriteFailed(message)
+            | AppError::SshFailed(message)
+            | AppError::AuthFailed(message)
+            | AppError::Unreachable(message)
+            | AppError::Timeout(message)
+            | AppError::ResolveFailed(message)
+            | AppError::ParseFailed(message)
+            | AppError::TerminalLaunch(message)
+            | AppError::OpenFailed(message)
+            | AppError::EventEmit(message)
+            | AppError::PermissionDenied(message) => f.write_str(message),
+        }
+    }
 }
 
+impl std::error::Error for AppError {}
+
 // =========================================
 // 2. Tauri Commands
 // =========================================
 
-#[tauri::command]
-async fn check_connection_status(target: String) -> bool {
-    check_ping(&target)
+#[tauri::command]
+#[instrument(err)]
+async fn check_connection_status(
+    target: String,
+    timeout_ms: Option<u32>,
+    ttl_ms: Option<u64>,
+    retries: Option<u32>,
+) -> Result<PingState, AppError> {
+    validate_target(&target)?;
+    let timeout_ms = timeout_ms.unwrap_or_else(|| settings().ping_timeout_ms);
+    if timeout_ms == 0 {
+        return Err(AppError::InvalidInput(
+            "Ping timeout must be greater than 0ms".to_string(),
+        ));
+    }
+
+    // Serve a recent enough result without spawning another ping
+    if let Some(ttl_ms) = ttl_ms {
+        if let Some(state) = cached_ping(&target, Duration::from_millis(ttl_ms)) {
+            return Ok(state);
+        }
+    }
+
+    // Retries sleep between pings, so keep the whole probe off the runtime workers
+    let ping_target = target.clone();
+    let state = tokio::task::spawn_blocking(move || {
+        check_ping(&ping_target, timeout_ms, retries.unwrap_or(0))
+    })
+    .await
+    .unwrap_or(PingState::Unknown);
+    if state != PingState::Unknown {
+        ping_cache()
+            .lock()
+            .unwrap()
+            .insert(target, (Instant::now(), state));
+    }
+    Ok(state)
+}
+
+#[tauri::command]
+#[instrument]
+fn clear_ping_cache() {
+    ping_cache().lock().unwrap().clear();
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn measure_latency(target: String) -> Result<Option<f64>, AppError> {
+    validate_target(&target)?;
+    ping_latency(&target, 0).await
+}
+
+#[tauri::command]
+#[instrument(skip(app))]
+async fn check_batch_latency(
+    app: AppHandle,
+    mut targets: Vec<String>,
+    max_concurrency: Option<usize>,
+    scan_id: Option<String>,
+) -> HashMap<String, HostLatency> {
+    // Bound the pings in flight so a large status grid doesn't spawn them all at once
+    let limit = max_concurrency
+        .unwrap_or_else(|| settings().max_concurrency)
+        .max(1);
+    let permits = Arc::new(Semaphore::new(limit));
+
+    // The same IP can be listed twice (one robot, two interfaces); ping it only once
+    // so duplicates don't hold permits. The map still has one key per unique target.
+    let mut seen = HashSet::new();
+    targets.retain(|target| seen.insert(target.clone()));
+
+    let tasks = targets.iter().cloned().map(|target| {
+        let permits = permits.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            // Invalid targets and ping failures are reported as unreachable
+            if validate_target(&target).is_err() {
+                return HostLatency::without_time(PingState::Offline);
+            }
+            match ping_latency(&target, BATCH_PING_RETRIES).await {
+                Ok(Some(latency_ms)) => HostLatency {
+                    state: PingState::Online,
+                    latency_ms: Some(latency_ms),
+                },
+                Ok(None) => HostLatency::without_time(PingState::Offline),
+                // It answered; only the round-trip time is unknown
+                Err(AppError::ParseFailed(_)) => HostLatency::without_time(PingState::Online),
+                Err(AppError::ToolMissing(_)) => HostLatency::without_time(PingState::ToolMissing),
+                Err(_) => HostLatency::without_time(PingState::Offline),
+            }
+        })
+    });
+
+    // A panicked task still gets an Unknown entry, so every unique target is in the map
+    let results: HashMap<String, HostLatency> = join_all(tasks)
+        .await
+        .into_iter()
+        .zip(targets)
+        .map(|(result, target)| {
+            let latency = result.unwrap_or_else(|e| {
+                warn!(%target, error = %e, "latency task failed");
+                HostLatency::without_time(PingState::Unknown)
+            });
+            (target, latency)
+        })
+        .collect();
+
+    // Fire-and-forget callers identify their scan by id and listen instead of awaiting
+    if let Some(scan_id) = scan_id {
+        let event = BatchCompleteEvent {
+            scan_id,
+            results: results.clone(),
+        };
+        if let Err(e) = app.emit("batch-complete", event) {
+            warn!(error = %e, "failed to emit batch-complete");
+        }
+    }
+    results
+}
+
+#[tauri::command]
+#[instrument]
+async fn check_batch_detailed(
+    targets: Vec<String>,
+    max_concurrency: Option<usize>,
+) -> Vec<HostStatus> {
+    let limit = max_concurrency
+        .unwrap_or_else(|| settings().max_concurrency)
+        .max(1);
+    let permits = Arc::new(Semaphore::new(limit));
+
+    // Duplicates are kept so the output lines up one-to-one with the UI grid
+    let tasks = targets.iter().cloned().map(|target| {
+        let permits = permits.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let resolve_target = target.clone();
+            let resolved = tokio::task::spawn_blocking(move || {
+                validate_target(&resolve_target).and_then(|()| resolve_ips(&resolve_target))
+            })
+            .await;
+            let Ok(Ok(ips)) = resolved else {
+                return HostStatus {
+                    target,
+                    online: false,
+                    latency_ms: None,
+                    resolved_ip: None,
+                };
+            };
+
+            // Ping the address, not the name, so ping can't resolve it differently
+            let ip = ips[0].clone();
+            // Online comes from ping's exit status; the time is best effort on top of it
+            let reply = ping_reply(&ip, BATCH_PING_RETRIES).await.unwrap_or(None);
+            HostStatus {
+                target,
+                online: reply.is_some(),
+                latency_ms: reply.as_deref().and_then(parse_ping_rtt),
+                resolved_ip: Some(ip),
+            }
+        })
+    });
+
+    // join_all keeps the input order; a panicked task still gets an offline entry
+    join_all(tasks)
+        .await
+        .into_iter()
+        .zip(targets)
+        .map(|(result, target)| {
+            result.unwrap_or(HostStatus {
+                target,
+                online: false,
+                latency_ms: None,
+                resolved_ip: None,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn measure_link_quality(
+    target: String,
+    count: u32,
+    ping_options: Option<PingOptions>,
+) -> Result<LinkQuality, AppError> {
+    validate_target(&target)?;
+    if count == 0 {
+        return Err(AppError::InvalidInput(
+            "Ping count must be greater than 0".to_string(),
+        ));
+    }
+    let ping_opts = ping_options.unwrap_or_default();
+    validate_ping_options(&ping_opts)?;
+
+    // A run with 100% loss exits non-zero but still prints the statistics block,
+    // so the exit status is ignored and the output is parsed regardless.
+    let ping = ping_command(&target, count, settings().ping_timeout_ms, &ping_opts);
+    let output = tokio::process::Command::from(ping)
+        .output()
+        .await
+        .map_err(|e| AppError::spawn("ping", e))?;
+
+    parse_ping_statistics(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+        AppError::ParseFailed(format!("Could not parse ping statistics for {}", target))
+    })
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn ping_verbose(
+    target: String,
+    ping_options: Option<PingOptions>,
+) -> Result<String, AppError> {
+    validate_target(&target)?;
+    let ping_opts = ping_options.unwrap_or_default();
+    validate_ping_options(&ping_opts)?;
+
+    // Diagnostic view: the exit status is irrelevant, the operator wants ping's own words
+    let ping = ping_command(&target, 1, settings().ping_timeout_ms, &ping_opts);
+    let output = tokio::process::Command::from(ping)
+        .output()
+        .await
+        .map_err(|e| AppError::spawn("ping", e))?;
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(strip_ansi(&combined))
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn resolve_host(hostname: String) -> Result<Vec<String>, AppError> {
+    validate_target(&hostname)?;
+    tokio::task::spawn_blocking(move || resolve_ips(&hostname))
+        .await
+        .map_err(|e| AppError::ResolveFailed(format!("Resolver task failed: {}", e)))?
+}
+
+#[tauri::command]
+#[instrument]
+async fn ping_with_resolution(target: String) -> PingResult {
+    let resolve_target = target.clone();
+    let resolved = tokio::task::spawn_blocking(move || {
+        validate_target(&resolve_target).and_then(|()| resolve_ips(&resolve_target))
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err(AppError::ResolveFailed(format!(
+            "Resolver task failed: {}",
+            e
+        )))
+    });
+    let ip = match resolved {
+        Ok(ips) => ips[0].clone(),
+        Err(e) => {
+            return PingResult {
+                resolved_ip: None,
+                reachable: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    // Ping the address, not the name, so ping can't resolve it differently
+    let ping_ip = ip.clone();
+    let state =
+        tokio::task::spawn_blocking(move || check_ping(&ping_ip, settings().ping_timeout_ms, 0))
+            .await
+            .unwrap_or(PingState::Offline);
+    PingResult {
+        resolved_ip: Some(ip),
+        reachable: state == PingState::Online,
+        error: (state == PingState::ToolMissing)
+            .then(|| "ping is not installed or not on PATH".to_string()),
+    }
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+async fn scan_subnet(
+    app: AppHandle,
+    cidr: String,
+    max_concurrent: usize,
+) -> Result<Vec<String>, AppError> {
+    let addresses = expand_cidr(&cidr)?;
+    let total = addresses.len();
+    let permits = Arc::new(Semaphore::new(max_concurrent.max(1)));
+    let scanned = Arc::new(AtomicUsize::new(0));
+
+    let tasks = addresses.iter().copied().map(|addr| {
+        let permits = permits.clone();
+        let scanned = scanned.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let target = addr.to_string();
+            let state = tokio::task::spawn_blocking(move || {
+                check_ping(&target, settings().ping_timeout_ms, 0)
+            })
+            .await
+            .unwrap_or(PingState::Offline);
+
+            let scanned = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            // Progress is best-effort; a dropped event only stalls the bar
+            let _ = app.emit("subnet-scan-progress", ScanProgressEvent { scanned, total });
+            state
+        })
+    });
+
+    // join_all keeps input order, so responders come back sorted by address.
+    // A panicked task counts as no reply, but is logged rather than silently dropped.
+    Ok(join_all(tasks)
+        .await
+        .into_iter()
+        .zip(addresses)
+        .filter(|(result, addr)| match result {
+            Ok(state) => *state == PingState::Online,
+            Err(e) => {
+                warn!(%addr, error = %e, "scan task failed");
+                false
+            }
+        })
+        .map(|(_, addr)| addr.to_string())
+        .collect())
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn validate_cidr(cidr: String) -> Result<CidrInfo, AppError> {
+    let (network, prefix_len, hosts) = parse_cidr(&cidr)?;
+    Ok(CidrInfo {
+        network: network.to_string(),
+        prefix_len,
+        host_count: hosts.end() - hosts.start() + 1,
+    })
+}
+
+#[tauri::command]
+#[instrument]
+async fn check_tcp_port(host: String, port: u16, timeout_ms: u64) -> bool {
+    if validate_target(&host).is_err() {
+        return false;
+    }
+    tokio::task::spawn_blocking(move || check_tcp(&host, port, Duration::from_millis(timeout_ms)))
+        .await
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn start_host_monitor(
+    app: AppHandle,
+    target: String,
+    interval_ms: u64,
+) -> Result<String, AppError> {
+    validate_target(&target)?;
+    if interval_ms == 0 {
+        return Err(AppError::InvalidInput(
+            "Monitor interval must be greater than 0ms".to_string(),
+        ));
+    }
+
+    static NEXT_MONITOR_ID: AtomicU64 = AtomicU64::new(0);
+    let id = format!(
+        "monitor-{}",
+        NEXT_MONITOR_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let monitor_id = id.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut last_state = None;
+        loop {
+            ticker.tick().await;
+            let ping_target = target.clone();
+            let Ok(state) = tokio::task::spawn_blocking(move || {
+                check_ping(&ping_target, settings().ping_timeout_ms, BATCH_PING_RETRIES)
+            })
+            .await
+            else {
+                continue;
+            };
+
+            // The first probe only sets the baseline; after that, report flips only
+            let changed = last_state.is_some_and(|last| last != state);
+            last_state = Some(state);
+            if changed {
+                let event = HostStateChangedEvent {
+                    monitor_id: monitor_id.clone(),
+                    target: target.clone(),
+                    state,
+                };
+                if let Err(e) = app.emit("host-state-changed", event) {
+                    warn!(error = %e, "failed to emit host-state-changed");
+                }
+            }
+        }
+    });
+    monitor_registry().lock().unwrap().insert(id.clone(), task);
+    Ok(id)
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn stop_host_monitor(id: String) -> Result<(), AppError> {
+    match monitor_registry().lock().unwrap().remove(&id) {
+        Some(task) => {
+            task.abort();
+            Ok(())
+        }
+        None => Err(AppError::NotFound(format!(
+            "No host monitor with id {}",
+            id
+        ))),
+    }
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+#[allow(clippy::too_many_arguments)]
+async fn open_ssh_terminal(
+    app: AppHandle,
+    hostname: String,
+    ip: String,
+    run_ros: Option<bool>,
+    remote_command: Option<String>,
+    env: Option<HashMap<String, String>>,
+    preset_name: Option<String>,
+    ssh_options: Option<SshOptions>,
+    terminal_options: Option<TerminalOptions>,
+) -> Result<u32, AppError> {
+    let mut ssh_opts = ssh_options.unwrap_or_default();
+    let (run_ros, remote_command) =
+        host_preset(&app, preset_name.as_deref())?.apply(run_ros, remote_command, &mut ssh_opts);
+    let shell_args = terminal_shell_command(
+        &hostname,
+        &ip,
+        run_ros,
+        &remote_command,
+        &env.unwrap_or_default(),
+        &ssh_opts,
+    )?;
+
+    let (mode, term_opts) = ssh_terminal_layout(hostname, terminal_options);
+    launch_terminal_with_timeout(shell_args, mode, term_opts).await
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+#[allow(clippy::too_many_arguments)]
+fn preview_ssh_terminal(
+    app: AppHandle,
+    hostname: String,
+    ip: String,
+    run_ros: Option<bool>,
+    remote_command: Option<String>,
+    env: Option<HashMap<String, String>>,
+    preset_name: Option<String>,
+    ssh_options: Option<SshOptions>,
+    terminal_options: Option<TerminalOptions>,
+) -> Result<String, AppError> {
+    let mut ssh_opts = ssh_options.unwrap_or_default();
+    let (run_ros, remote_command) =
+        host_preset(&app, preset_name.as_deref())?.apply(run_ros, remote_command, &mut ssh_opts);
+    let shell_args = terminal_shell_command(
+        &hostname,
+        &ip,
+        run_ros,
+        &remote_command,
+        &env.unwrap_or_default(),
+        &ssh_opts,
+    )?;
+
+    let (mode, term_opts) = ssh_terminal_layout(hostname, terminal_options);
+    preview_terminal_launch(&shell_args, mode, &term_opts)
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+async fn exec_shutdown_command(
+    app: AppHandle,
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+    confirm_timeout_secs: Option<u32>,
+    ip: Option<String>,
+) -> Result<u32, AppError> {
+    // The ssh alias may not resolve for ping, so the caller can give the address to poll
+    let poll_target = ip.unwrap_or_else(|| hostname.clone());
+    if confirm_timeout_secs.is_some() {
+        validate_target(&poll_target)?;
+    }
+
+    let pid = launch_remote_admin_command(&hostname, ssh_options, SHUTDOWN_COMMAND).await?;
+    if let Some(timeout_secs) = confirm_timeout_secs {
+        spawn_shutdown_confirmation(app, hostname, poll_target, timeout_secs);
+    }
+    Ok(pid)
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn preview_shutdown_command(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    preview_remote_admin_command(&hostname, ssh_options, SHUTDOWN_COMMAND)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn safe_shutdown(
+    hostname: String,
+    stop_command: Option<String>,
+    ssh_options: Option<SshOptions>,
+) -> Result<u32, AppError> {
+    let command = safe_shutdown_command(stop_command);
+    launch_remote_admin_command(&hostname, ssh_options, &command).await
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn preview_safe_shutdown(
+    hostname: String,
+    stop_command: Option<String>,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    let command = safe_shutdown_command(stop_command);
+    preview_remote_admin_command(&hostname, ssh_options, &command)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn exec_reboot_command(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<u32, AppError> {
+    launch_remote_admin_command(&hostname, ssh_options, REBOOT_COMMAND).await
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn preview_reboot_command(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    preview_remote_admin_command(&hostname, ssh_options, REBOOT_COMMAND)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn restart_ros(
+    hostname: String,
+    restart_command: Option<String>,
+    ssh_options: Option<SshOptions>,
+) -> Result<u32, AppError> {
+    let ssh_args = restart_ros_ssh_command(&hostname, ssh_options, restart_command)?;
+    // Open next to the operator's existing terminals
+    launch_terminal_with_timeout(ssh_args, WindowMode::Tab, TerminalOptions::default()).await
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn preview_restart_ros(
+    hostname: String,
+    restart_command: Option<String>,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    let ssh_args = restart_ros_ssh_command(&hostname, ssh_options, restart_command)?;
+    preview_terminal_launch(&ssh_args, WindowMode::Tab, &TerminalOptions::default())
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn run_remote_command(
+    hostname: String,
+    command: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<RemoteResult, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    remote_command_result(&hostname, &ssh_opts, &command).await
+}
+
+#[tauri::command]
+#[instrument]
+async fn run_remote_command_batch(
+    mut hosts: Vec<String>,
+    command: String,
+    ssh_options: Option<SshOptions>,
+    max_concurrency: Option<usize>,
+) -> HashMap<String, RemoteResult> {
+    let limit = max_concurrency
+        .unwrap_or_else(|| settings().max_concurrency)
+        .max(1);
+    let permits = Arc::new(Semaphore::new(limit));
+    let ssh_opts = Arc::new(ssh_options.unwrap_or_default());
+
+    let mut seen = HashSet::new();
+    hosts.retain(|host| seen.insert(host.clone()));
+
+    let tasks = hosts.iter().cloned().map(|host| {
+        let permits = permits.clone();
+        let ssh_opts = ssh_opts.clone();
+        let command = command.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            // A host that can't be reached still gets an entry, with the error as stderr
+            remote_command_result(&host, &ssh_opts, &command)
+                .await
+                .unwrap_or_else(|e| RemoteResult {
+                    stdout: String::new(),
+                    stderr: e.to_string(),
+                    exit_code: None,
+                })
+        })
+    });
+
+    // A panicked task also gets an entry, so every unique host is in the map
+    join_all(tasks)
+        .await
+        .into_iter()
+        .zip(hosts)
+        .map(|(result, host)| {
+            let result = result.unwrap_or_else(|e| RemoteResult {
+                stdout: String::new(),
+                stderr: format!("Command task failed: {}", e),
+                exit_code: None,
+            });
+            (host, result)
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn test_ssh_auth(hostname: String, ssh_options: Option<SshOptions>) -> Result<(), AppError> {
+    let mut ssh_opts = ssh_options.unwrap_or_default();
+    // Fail fast; the caller only wants to know whether a session would work
+    ssh_opts.connect_timeout_secs.get_or_insert(5);
+
+    let output = ssh_run(&hostname, &ssh_opts, "true").await?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.code() == Some(SSH_ERROR_EXIT) && stderr.contains("Permission denied") {
+        return Err(AppError::AuthFailed(format!(
+            "Authentication to {} was rejected: {}",
+            hostname,
+            stderr.trim()
+        )));
+    }
+    Err(AppError::ssh(
+        format!("Could not connect to {}", hostname),
+        &output,
+    ))
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn measure_ssh_rtt(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<f64, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+
+    // Connection setup and auth are included; with `multiplex` and an open master
+    // connection this only measures the warm path
+    let started = Instant::now();
+    let output = ssh_run(&hostname, &ssh_opts, "true").await?;
+    let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    if !output.status.success() {
+        return Err(AppError::ssh(
+            format!("Could not run a command on {}", hostname),
+            &output,
+        ));
+    }
+    Ok(elapsed_ms)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn fetch_ros_logs(
+    hostname: String,
+    lines: u32,
+    log_path: Option<String>,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    if lines == 0 {
+        return Err(AppError::InvalidInput(
+            "Line count must be greater than 0".to_string(),
+        ));
+    }
+
+    let log_path = log_path.unwrap_or_else(|| DEFAULT_ROS_LOG_PATH.to_string());
+    let remote_cmd = format!("tail -n {} {}", lines, remote_path_arg(&log_path));
+    ssh_read(
+        &hostname,
+        &ssh_options.unwrap_or_default(),
+        &remote_cmd,
+        format!("Failed to fetch ROS logs from {}", hostname),
+        format!("No ROS log found at {} on {}", log_path, hostname),
+    )
+    .await
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn tail_journal(
+    hostname: String,
+    unit: String,
+    lines: u32,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    if lines == 0 {
+        return Err(AppError::InvalidInput(
+            "Line count must be greater than 0".to_string(),
+        ));
+    }
+    validate_unit_name(&unit)?;
+
+    // For stacks run as a systemd service, whose logs never reach ~/.ros/log
+    let remote_cmd = format!("journalctl -u {} -n {} --no-pager", unit, lines);
+    ssh_output(&hostname, &ssh_options.unwrap_or_default(), &remote_cmd).await
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn read_remote_file(
+    hostname: String,
+    path: String,
+    max_bytes: usize,
+    ssh_options: Option<SshOptions>,
+) -> Result<String, AppError> {
+    validate_remote_path(&path)?;
+    if max_bytes == 0 || max_bytes > MAX_REMOTE_FILE_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "max_bytes must be between 1 and {}",
+            MAX_REMOTE_FILE_BYTES
+        )));
+    }
+
+    let remote_cmd = format!("head -c {} {}", max_bytes, remote_path_arg(&path));
+    ssh_read(
+        &hostname,
+        &ssh_options.unwrap_or_default(),
+        &remote_cmd,
+        format!("Failed to read {} on {}", path, hostname),
+        format!("No file at {} on {}", path, hostname),
+    )
+    .await
+}
+
+#[tauri::command]
+#[instrument(skip(contents), err)]
+async fn write_remote_file(
+    hostname: String,
+    path: String,
+    contents: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<(), AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    validate_target(&hostname)?;
+    validate_ssh_options(&ssh_opts)?;
+    validate_remote_path(&path)?;
+
+    // Contents go over stdin, so they are never interpolated into a shell.
+    // The original is kept as <path>.bak, and the byte count written back is
+    // printed so the write can be checked.
+    let target = remote_path_arg(&path);
+    let backup = remote_path_arg(&format!("{}.bak", path));
+    let remote_cmd = format!(
+        "if [ -e {target} ]; then cp -p {target} {backup}; fi && cat > {target} && wc -c < {target}"
+    );
+
+    let mut child =
+        tokio::process::Command::from(ssh_exec_command(&hostname, &ssh_opts, &remote_cmd))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AppError::spawn("ssh", e))?;
+    // Dropping stdin sends EOF so the remote `cat` finishes
+    let sent = match child.stdin.take() {
+        Some(mut stdin) => stdin.write_all(contents.as_bytes()).await,
+        None => Ok(()),
+    };
+    // Wait even if the write failed: a broken pipe means ssh exited, and its stderr says why
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| AppError::spawn("ssh", e))?;
+
+    if !output.status.success() {
+        return Err(AppError::ssh(
+            format!("Failed to write {} on {}", path, hostname),
+            &output,
+        ));
+    }
+    sent.map_err(|e| AppError::SshFailed(format!("Failed to send file contents: {}", e)))?;
+
+    let written: Option<usize> = String::from_utf8_lossy(&output.stdout).trim().parse().ok();
+    if written != Some(contents.len()) {
+        return Err(AppError::SshFailed(format!(
+            "Could not verify the write to {} on {}: expected {} bytes, remote reported {:?}",
+            path,
+            hostname,
+            contents.len(),
+            written
+        )));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn get_disk_usage(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<Vec<DiskInfo>, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    let remote_cmd =
+        "df -B1 --output=target,size,used,avail -x tmpfs -x devtmpfs -x squashfs -x overlay";
+    let stdout = ssh_output(&hostname, &ssh_opts, remote_cmd).await?;
+
+    Ok(parse_df_output(&stdout))
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn get_system_vitals(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<Vitals, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    // Read both files in one round-trip, separated by a sentinel line
+    let remote_cmd = format!(
+        "cat /proc/loadavg && echo {} && cat /proc/meminfo",
+        VITALS_SENTINEL
+    );
+    let stdout = ssh_output(&hostname, &ssh_opts, &remote_cmd).await?;
+
+    parse_vitals(&stdout)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn check_clock_skew(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<f64, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    validate_target(&hostname)?;
+
+    let sent_at = unix_time_secs();
+    let stdout = ssh_output(&hostname, &ssh_opts, "date +%s.%N").await?;
+    let received_at = unix_time_secs();
+
+    let remote_time: f64 = stdout
+        .trim()
+        .parse()
+        .map_err(|_| AppError::ParseFailed(format!("Unexpected date output: {}", stdout.trim())))?;
+
+    // Assume the remote clock was read halfway through the round trip, so the
+    // estimate is only good to +/- half the ssh round-trip time.
+    // Positive means the robot is ahead of this machine.
+    let local_midpoint = sent_at + (received_at - sent_at) / 2.0;
+    Ok(remote_time - local_midpoint)
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn list_ros_topics(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<Vec<String>, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    let remote_cmd = ros2_start_command("ros2 topic list");
+    let stdout = ssh_output(&hostname, &ssh_opts, &remote_cmd).await?;
+
+    // Keep only topic names; drop blank lines and any shell/log noise
+    let mut topics: Vec<String> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('/'))
+        .map(String::from)
+        .collect();
+    topics.sort();
+    topics.dedup();
+    Ok(topics)
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+async fn echo_ros_topic(
+    app: AppHandle,
+    hostname: String,
+    topic: String,
+    duration_secs: u32,
+    ssh_options: Option<SshOptions>,
+) -> Result<(), AppError> {
+    if duration_secs == 0 {
+        return Err(AppError::InvalidInput(
+            "Echo duration must be greater than 0s".to_string(),
+        ));
+    }
+    let ssh_opts = ssh_options.unwrap_or_default();
+    validate_target(&hostname)?;
+    validate_ssh_options(&ssh_opts)?;
+
+    // `timeout` runs remotely so the echo stops on the robot even if only the local ssh is killed
+    let echo = format!(
+        "timeout {} ros2 topic echo {}",
+        duration_secs,
+        shell_quote(&topic)
+    );
+    let remote_cmd = ros2_start_command(&echo);
+
+    let mut child =
+        tokio::process::Command::from(ssh_exec_command(&hostname, &ssh_opts, &remote_cmd))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AppError::spawn("ssh", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::SpawnFailed("Failed to capture ssh output".to_string()))?;
+    let child_id = register_child(child);
+
+    // Messages are separated by `---` lines
+    let emit_message = |message: &str| {
+        let event = TopicEchoEvent {
+            topic: topic.clone(),
+            message: message.trim_end().to_string(),
+        };
+        app.emit("ros-topic-echo", event)
+            .map_err(|e| AppError::EventEmit(format!("Failed to emit ros-topic-echo: {}", e)))
+    };
+    let read_messages = async {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut message = String::new();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let line = strip_ansi(&line);
+            if line.trim() == "---" {
+                emit_message(&message)?;
+                message.clear();
+            } else {
+                message.push_str(&line);
+                message.push('\n');
+            }
+        }
+        if !message.trim().is_empty() {
+            emit_message(&message)?;
+        }
+        Ok::<(), AppError>(())
+    };
+
+    // Give the remote `timeout` a few seconds of slack before killing ssh locally
+    let deadline = Duration::from_secs(u64::from(duration_secs) + 5);
+    let result = tokio::time::timeout(deadline, read_messages).await;
+    kill_child(child_id);
+
+    result.unwrap_or(Ok(()))
+}
+
+#[tauri::command]
+#[instrument(err)]
+async fn close_ssh_multiplex(
+    hostname: String,
+    ssh_options: Option<SshOptions>,
+) -> Result<(), AppError> {
+    validate_target(&hostname)?;
+    let mut ssh_opts = ssh_options.unwrap_or_default();
+    validate_ssh_options(&ssh_opts)?;
+    ssh_opts.multiplex = true;
+    if !multiplex_supported(&ssh_opts) {
+        return Err(AppError::InvalidInput(
+            "Connection multiplexing needs ssh on Linux, macOS or WSL".to_string(),
+        ));
+    }
+
+    // Same options as the connection itself so %C hashes to the same socket
+    let mut cmd = ssh_program(&ssh_opts);
+    cmd.args(["-O", "exit"])
+        .args(ssh_option_args(&ssh_opts))
+        .arg(ssh_destination(&hostname, &ssh_opts));
+    let output = tokio::process::Command::from(cmd)
+        .output()
+        .await
+        .map_err(|e| AppError::spawn("ssh", e))?;
+
+    if !output.status.success() {
+        return Err(AppError::ssh(
+            format!("Failed to close multiplexed connection to {}", hostname),
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument]
+fn check_local_prerequisites() -> Vec<PrereqStatus> {
+    let mut prereqs: Vec<PrereqStatus> = ["ssh", "ping"].into_iter().map(prereq_status).collect();
+    prereqs.push(terminal_prereq_status());
+
+    for prereq in prereqs.iter().filter(|prereq| !prereq.available) {
+        warn!(name = prereq.name, "local prerequisite missing");
+    }
+    prereqs
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn load_hosts_config(path: String) -> Result<Vec<HostEntry>, AppError> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            AppError::NotFound(format!("Hosts config not found: {}", path))
+        } else {
+            AppError::ReadFailed(format!("Failed to read {}: {}", path, e))
+        }
+    })?;
+
+    let config = parse_hosts_config(&path, &contents)?;
+    validate_hosts(&config.hosts)?;
+    Ok(config.hosts)
+}
+
+#[tauri::command]
+#[instrument(err)]
+fn list_local_interfaces() -> Result<Vec<IfaceInfo>, AppError> {
+    let addrs = if_addrs::get_if_addrs()
+        .map_err(|e| AppError::ReadFailed(format!("Failed to list network interfaces: {}", e)))?;
+
+    // get_if_addrs returns one entry per address; group them by interface
+    let mut ifaces: Vec<IfaceInfo> = Vec::new();
+    for iface in addrs {
+        let index = match ifaces.iter().position(|info| info.name == iface.name) {
+            Some(index) => index,
+            None => {
+                ifaces.push(IfaceInfo {
+                    name: iface.name.clone(),
+                    ipv4: Vec::new(),
+                    ipv6: Vec::new(),
+                    is_up: false,
+                });
+                ifaces.len() - 1
+            }
+        };
+        let info = &mut ifaces[index];
+        info.is_up |= iface.is_oper_up();
+        match &iface.addr {
+            if_addrs::IfAddr::V4(v4) => info.ipv4.push(format!("{}/{}", v4.ip, v4.prefixlen)),
+            if_addrs::IfAddr::V6(v6) => info.ipv6.push(format!("{}/{}", v6.ip, v6.prefixlen)),
+        }
+    }
+    Ok(ifaces)
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+async fn generate_diagnostics_bundle(app: AppHandle, hostname: String) -> Result<String, AppError> {
+    let mut errors = Vec::new();
+    let interfaces = list_local_interfaces().unwrap_or_else(|e| {
+        errors.push(e.to_string());
+        Vec::new()
+    });
+    let settings = load_settings(app)
+        .map_err(|e| errors.push(e.to_string()))
+        .ok();
+    let bundle = DiagnosticsBundle {
+        schema_version: DIAGNOSTICS_SCHEMA_VERSION,
+        generated_at: unix_time_secs(),
+        hostname: hostname.clone(),
+        ping: ping_with_resolution(hostname.clone()).await,
+        prerequisites: check_local_prerequisites(),
+        interfaces,
+        settings,
+        errors,
+    };
+
+    // The hostname may be an IPv6 literal; keep the file name portable
+    let safe_name: String = hostname
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = std::env::temp_dir().join(format!(
+        "kyubic-diagnostics-{}-{}.json",
+        safe_name,
+        unix_time_secs() as u64
+    ));
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::WriteFailed(format!("Failed to serialize diagnostics: {}", e)))?;
+    std::fs::write(&path, json)
+        .map_err(|e| AppError::WriteFailed(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn open_in_file_manager(app: AppHandle, path: String) -> Result<(), AppError> {
+    let dir = Path::new(&path);
+    if !dir.exists() {
+        return Err(AppError::NotFound(format!("Directory not found: {}", path)));
+    }
+    if !dir.is_dir() {
+        return Err(AppError::InvalidInput(format!("Not a directory: {}", path)));
+    }
+
+    // Opening a directory with the default handler shows it in Explorer/Finder/etc.
+    app.opener()
+        .open_path(path.as_str(), None::<&str>)
+        .map_err(|e| AppError::OpenFailed(format!("Failed to open {}: {}", path, e)))
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn load_settings(app: AppHandle) -> Result<AppSettings, AppError> {
+    let settings: AppSettings = read_config_json(&config_file_path(&app, SETTINGS_FILE_NAME)?)?;
+    *app_settings().lock().unwrap() = settings.clone();
+    Ok(settings)
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn save_settings(app: AppHandle, settings: AppSettings) -> Result<(), AppError> {
+    if settings.ssh_port == 0 || settings.ping_timeout_ms == 0 || settings.max_concurrency == 0 {
+        return Err(AppError::InvalidInput(
+            "sshPort, pingTimeoutMs and maxConcurrency must be greater than 0".to_string(),
+        ));
+    }
+    write_config_json(&config_file_path(&app, SETTINGS_FILE_NAME)?, &settings)?;
+    *app_settings().lock().unwrap() = settings;
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn get_host_preset(app: AppHandle, name: String) -> Result<Option<HostPreset>, AppError> {
+    let mut presets: HashMap<String, HostPreset> =
+        read_config_json(&config_file_path(&app, HOST_PRESETS_FILE_NAME)?)?;
+    Ok(presets.remove(&name))
+}
+
+#[tauri::command]
+#[instrument(skip(app), err)]
+fn save_host_preset(app: AppHandle, name: String, preset: HostPreset) -> Result<(), AppError> {
+    let path = config_file_path(&app, HOST_PRESETS_FILE_NAME)?;
+    let mut presets: HashMap<String, HostPreset> = read_config_json(&path)?;
+    presets.insert(name, preset);
+    write_config_json(&path, &presets)
+}
+
+// =========================================
+// 3. Core Logic Helpers
+// =========================================
+
+/// Runs a one-off remote command (shutdown, reboot, ...) over `ssh -t`.
+async fn launch_remote_admin_command(
+    hostname: &str,
+    ssh_options: Option<SshOptions>,
+    command: &str,
+) -> Result<u32, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    let ssh_args = remote_admin_ssh_command(hostname, &ssh_opts, command)?;
+
+    // Open in a new independent window to avoid cluttering the main workflow
+    launch_terminal_with_timeout(ssh_args, WindowMode::NewWindow, TerminalOptions::default()).await
+}
+
+/// What launch_remote_admin_command would run for `command`, without running it.
+fn preview_remote_admin_command(
+    hostname: &str,
+    ssh_options: Option<SshOptions>,
+    command: &str,
+) -> Result<String, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    let ssh_args = remote_admin_ssh_command(hostname, &ssh_opts, command)?;
+    preview_terminal_launch(
+        &ssh_args,
+        WindowMode::NewWindow,
+        &TerminalOptions::default(),
+    )
+}
+
+/// Formats the launcher process (wt, osascript, gnome-terminal, ...) that
+/// launch_terminal would start first for `ssh_args`, including all of its wrapping.
+fn preview_terminal_launch(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<String, AppError> {
+    validate_terminal_options(term_opts)?;
+    Ok(format!(
+        "{:?}",
+        terminal_command(ssh_args, mode, term_opts)?
+    ))
+}
+
+/// open_ssh_terminal's defaults: a tab (or split pane) titled with the hostname.
+fn ssh_terminal_layout(
+    hostname: String,
+    terminal_options: Option<TerminalOptions>,
+) -> (WindowMode, TerminalOptions) {
+    let mut term_opts = terminal_options.unwrap_or_default();
+    term_opts.title.get_or_insert(hostname);
+    let mode = if term_opts.split_pane {
+        WindowMode::SplitPane
+    } else {
+        WindowMode::Tab
+    };
+    (mode, term_opts)
+}
+
+/// Stops ROS, then powers off. `&&`: if stopping fails the robot stays up and the
+/// terminal shows why, rather than cutting power on half-written bags.
+fn safe_shutdown_command(stop_command: Option<String>) -> String {
+    let stop_command = stop_command.unwrap_or_else(|| DEFAULT_ROS_STOP_COMMAND.to_string());
+    format!(
+        "{} && {}",
+        ros_shell_command(&stop_command),
+        SHUTDOWN_COMMAND
+    )
+}
+
+/// Validates the target and builds the `ssh -t` line that restarts the ROS stack.
+fn restart_ros_ssh_command(
+    hostname: &str,
+    ssh_options: Option<SshOptions>,
+    restart_command: Option<String>,
+) -> Result<String, AppError> {
+    let ssh_opts = ssh_options.unwrap_or_default();
+    validate_target(hostname)?;
+    validate_ssh_options(&ssh_opts)?;
+
+    let restart_command =
+        restart_command.unwrap_or_else(|| DEFAULT_ROS_RESTART_COMMAND.to_string());
+    let ros_command = ros_shell_command(&restart_command);
+    Ok(build_ssh_command(hostname, &ssh_opts, Some(&ros_command)))
+}
+
+/// Runs launch_terminal on the blocking pool and gives up after TERMINAL_LAUNCH_TIMEOUT.
+/// A wedged launcher (wt waiting on a busy Windows Terminal server, osascript behind
+/// the macOS consent dialog) can block for long; this keeps it off the runtime and the UI.
+async fn launch_terminal_with_timeout(
+    ssh_args: String,
+    mode: WindowMode,
+    term_opts: TerminalOptions,
+) -> Result<u32, AppError> {
+    validate_terminal_options(&term_opts)?;
+    let launch = tokio::task::spawn_blocking(move || launch_terminal(&ssh_args, mode, &term_opts));
+    match tokio::time::timeout(TERMINAL_LAUNCH_TIMEOUT, launch).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(AppError::TerminalLaunch(format!(
+            "Terminal launch task failed: {}",
+            e
+        ))),
+        Err(_) => Err(AppError::Timeout(format!(
+            "Terminal did not start within {}s",
+            TERMINAL_LAUNCH_TIMEOUT.as_secs()
+        ))),
+    }
+}
+
+/// Rejects a tab delay that would run the launcher past TERMINAL_LAUNCH_TIMEOUT.
+fn validate_terminal_options(opts: &TerminalOptions) -> Result<(), AppError> {
+    match opts.tab_delay_ms {
+        Some(delay) if delay > MAX_TAB_DELAY_MS => Err(AppError::InvalidInput(format!(
+            "Tab delay must be at most {}ms, got {}ms",
+            MAX_TAB_DELAY_MS, delay
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Validates the target and builds the `ssh -t` line for a remote admin command.
+fn remote_admin_ssh_command(
+    hostname: &str,
+    ssh_opts: &SshOptions,
+    command: &str,
+) -> Result<String, AppError> {
+    validate_target(hostname)?;
+    validate_ssh_options(ssh_opts)?;
+    Ok(build_ssh_command(hostname, ssh_opts, Some(command)))
+}
+
+/// Builds the command line open_ssh_terminal runs in the new terminal:
+/// a local shell for localhost, otherwise an ssh session (optionally into ROS).
+fn terminal_shell_command(
+    hostname: &str,
+    ip: &str,
+    run_ros: bool,
+    remote_command: &str,
+    env: &HashMap<String, String>,
+    ssh_opts: &SshOptions,
+) -> Result<String, AppError> {
+    // Detect Localhost
+    let is_local = ip == "127.0.0.1" || hostname == "localhost";
+    if !is_local {
+        validate_target(hostname)?;
+        validate_ssh_options(ssh_opts)?;
+    }
+
+    // Exported inside `bash -i -c`, after .bashrc, so they win over its defaults
+    let remote_command = &format!("{}{}", env_exports(env)?, remote_command);
+
+    // Build the SSH command arguments
+    let shell_args = if is_local {
+        // Local Mode
+        if run_ros {
+            ros_shell_command(remote_command)
+        } else {
+            if cfg!(target_os = "windows") {
+                "cmd /k echo Starting Local Terminal".to_string()
+            } else {
+                "echo 'Starting Local Terminal'".to_string()
+            }
+        }
+    } else {
+        // SSH Conection Mode
+        if run_ros {
+            let ros_command = ros_shell_command(remote_command);
+            build_ssh_command(hostname, ssh_opts, Some(&ros_command))
+        } else {
+            build_ssh_command(hostname, ssh_opts, None)
+        }
+    };
+    Ok(shell_args)
+}
+
+/// Builds the `ssh` command line passed to the terminal.
+/// With a `remote_command` a TTY is forced (`-t`) and the command is quoted for the local shell.
+fn build_ssh_command(hostname: &str, opts: &SshOptions, remote_command: Option<&str>) -> String {
+    // Inside WSL the line is parsed by bash even though the host OS is Windows
+    let posix_shell = !cfg!(target_os = "windows") || runs_in_wsl(opts);
+
+    let mut parts = vec!["ssh".to_string()];
+    if remote_command.is_some() {
+        parts.push("-t".to_string());
+    }
+    parts.extend(
+        ssh_option_args(opts)
+            .iter()
+            .map(|arg| quote_if_needed(arg, posix_shell)),
+    );
+    parts.push(quote_if_needed(
+        &ssh_destination(hostname, opts),
+        posix_shell,
+    ));
+    if let Some(command) = remote_command {
+        parts.push(quote_for_shell(command, posix_shell));
+    }
+
+    let ssh_line = parts.join(" ");
+    if runs_in_wsl(opts) {
+        let mut wsl = wsl_prefix(opts);
+        wsl.extend(["bash".to_string(), "-ic".to_string()]);
+        wsl.push(quote_for_shell(&ssh_line, false));
+        wsl.join(" ")
+    } else {
+        ssh_line
+    }
+}
+
+/// Builds a non-interactive `ssh` process that runs `remote_command` and exits.
+/// Arguments are passed directly (no local shell), and BatchMode stops ssh from
+/// waiting on a password prompt nobody can answer.
+fn ssh_exec_command(hostname: &str, opts: &SshOptions, remote_command: &str) -> Command {
+    let mut cmd = ssh_program(opts);
+    cmd.args(["-o", "BatchMode=yes"])
+        .args(ssh_option_args(opts))
+        .arg(ssh_destination(hostname, opts))
+        .arg(remote_command);
+
+    debug!(?cmd, "built command");
+    cmd
+}
+
+/// A bare `ssh` process without arguments, started through WSL when `opts` asks for it.
+fn ssh_program(opts: &SshOptions) -> Command {
+    let mut program = vec!["ssh".to_string()];
+    if runs_in_wsl(opts) {
+        program.splice(0..0, wsl_prefix(opts));
+    }
+    let mut cmd = Command::new(&program[0]);
+    cmd.args(&program[1..]);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// True when ssh should be run through WSL (only honoured on Windows).
+fn runs_in_wsl(opts: &SshOptions) -> bool {
+    cfg!(target_os = "windows") && opts.wsl
+}
+
+/// `wsl.exe [-d <distro>] -e`, ready for the program to run inside WSL.
+fn wsl_prefix(opts: &SshOptions) -> Vec<String> {
+    let mut args = vec!["wsl.exe".to_string()];
+    if let Some(distro) = opts.wsl_distro.as_deref().filter(|d| !d.is_empty()) {
+        args.extend(["-d".to_string(), distro.to_string()]);
+    }
+    args.push("-e".to_string());
+    args
+}
+
+/// Runs `command` non-interactively and captures stdout, stderr and the exit code.
+/// A non-zero exit is a result, not an error; only validation and spawn failures are.
+async fn remote_command_result(
+    hostname: &str,
+    opts: &SshOptions,
+    command: &str,
+) -> Result<RemoteResult, AppError> {
+    let output = ssh_run(hostname, opts, command).await?;
+    Ok(RemoteResult {
+        stdout: strip_ansi(&String::from_utf8_lossy(&output.stdout)),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        exit_code: output.status.code(),
+    })
+}
+
+/// Validates `hostname` and `opts`, then runs `remote_command` non-interactively.
+/// The output comes back whatever the exit status; only validation and spawning fail.
+async fn ssh_run(
+    hostname: &str,
+    opts: &SshOptions,
+    remote_command: &str,
+) -> Result<Output, AppError> {
+    validate_target(hostname)?;
+    validate_ssh_options(opts)?;
+
+    tokio::process::Command::from(ssh_exec_command(hostname, opts, remote_command))
+        .output()
+        .await
+        .map_err(|e| AppError::spawn("ssh", e))
+}
+
+/// Like ssh_output for commands that read a remote file: a missing file becomes
+/// NotFound(`missing`), other failures an ssh error under `context`.
+async fn ssh_read(
+    hostname: &str,
+    opts: &SshOptions,
+    remote_command: &str,
+    context: String,
+    missing: String,
+) -> Result<String, AppError> {
+    let output = ssh_run(hostname, opts, remote_command).await?;
+    if !output.status.success() {
+        if String::from_utf8_lossy(&output.stderr).contains("No such file or directory") {
+            return Err(AppError::NotFound(missing));
+        }
+        return Err(AppError::ssh(context, &output));
+    }
+    Ok(strip_ansi(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Runs `remote_command` non-interactively and returns its ANSI-stripped stdout.
+/// A non-zero exit is turned into an error carrying ssh's stderr.
+async fn ssh_output(
+    hostname: &str,
+    opts: &SshOptions,
+    remote_command: &str,
+) -> Result<String, AppError> {
+    let output = ssh_run(hostname, opts, remote_command).await?;
+    if !output.status.success() {
+        return Err(AppError::ssh(
+            format!("Remote command failed on {} ({})", hostname, output.status),
+            &output,
+        ));
+    }
+
+    Ok(strip_ansi(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Current wall-clock time as fractional seconds since the Unix epoch.
+fn unix_time_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Parses `df -B1 --output=target,size,used,avail` output.
+/// The header row, pseudo filesystems and zero-sized mounts are skipped.
+fn parse_df_output(output: &str) -> Vec<DiskInfo> {
+    const PSEUDO_PREFIXES: [&str; 5] = ["/dev", "/proc", "/run", "/sys", "/snap"];
+
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // The mount point may contain spaces, so the numeric columns are taken from the right
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            let (mount, numbers) = fields.split_at(fields.len() - 3);
+            let mount = mount.join(" ");
+            let total_bytes: u64 = numbers[0].parse().ok()?;
+            let used_bytes: u64 = numbers[1].parse().ok()?;
+            let available_bytes: u64 = numbers[2].parse().ok()?;
+
+            let is_pseudo = PSEUDO_PREFIXES
+                .iter()
+                .any(|prefix| mount == *prefix || mount.starts_with(&format!("{}/", prefix)));
+            if is_pseudo || total_bytes == 0 {
+                return None;
+            }
+
+            Some(DiskInfo {
+                mount,
+                total_bytes,
+                used_bytes,
+                available_bytes,
+            })
+        })
+        .collect()
+}
+
+/// Location of `file_name` inside the Tauri app config dir.
+fn config_file_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(file_name))
+        .map_err(|e| AppError::NotFound(format!("No app config directory: {}", e)))
+}
+
+/// Reads a JSON config file, treating a missing file as `T::default()` (first launch).
+fn read_config_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, AppError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => {
+            return Err(AppError::ReadFailed(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    serde_json::from_str(&contents).map_err(|e| {
+        AppError::ParseFailed(format!("Invalid config file {}: {}", path.display(), e))
+    })
+}
+
+/// Writes `value` as pretty JSON, creating the config dir if needed.
+fn write_config_json<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    let write_failed = |e: std::io::Error| {
+        AppError::WriteFailed(format!("Failed to write {}: {}", path.display(), e))
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(write_failed)?;
+    }
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::WriteFailed(format!("Failed to serialize config: {}", e)))?;
+    std::fs::write(path, json).map_err(write_failed)
+}
+
+/// The preset saved under `name`, or an empty one when no name is given.
+fn host_preset(app: &AppHandle, name: Option<&str>) -> Result<HostPreset, AppError> {
+    let Some(name) = name else {
+        return Ok(HostPreset::default());
+    };
+    let mut presets: HashMap<String, HostPreset> =
+        read_config_json(&config_file_path(app, HOST_PRESETS_FILE_NAME)?)?;
+    presets
+        .remove(name)
+        .ok_or_else(|| AppError::NotFound(format!("No host preset named {}", name)))
+}
+
+impl HostPreset {
+    /// Fills the values the caller left unset from this preset and returns
+    /// the resolved `(run_ros, remote_command)`.
+    fn apply(
+        self,
+        run_ros: Option<bool>,
+        remote_command: Option<String>,
+        ssh_opts: &mut SshOptions,
+    ) -> (bool, String) {
+        if ssh_opts.port.is_none() {
+            ssh_opts.port = self.port;
+        }
+        if ssh_opts.username.is_none() {
+            ssh_opts.username = self.username;
+        }
+        (
+            run_ros.or(self.run_ros).unwrap_or(false),
+            remote_command.or(self.remote_command).unwrap_or_default(),
+        )
+    }
+}
+
+/// Parses a hosts file as TOML when the path ends in `.toml`, as JSON otherwise.
+fn parse_hosts_config(path: &str, contents: &str) -> Result<HostsConfig, AppError> {
+    let is_toml = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let parsed = if is_toml {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str(contents).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| AppError::ParseFailed(format!("Invalid hosts config {}: {}", path, e)))
+}
+
+/// Checks that every IP parses and that no two hosts share a name.
+fn validate_hosts(hosts: &[HostEntry]) -> Result<(), AppError> {
+    let mut names = HashSet::new();
+    for host in hosts {
+        if host.ip.parse::<IpAddr>().is_err() {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid IP address for {}: {}",
+                host.name, host.ip
+            )));
+        }
+        if !names.insert(host.name.as_str()) {
+            return Err(AppError::InvalidInput(format!(
+                "Duplicate host name in config: {}",
+                host.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parses `/proc/loadavg` and `/proc/meminfo` contents joined by `VITALS_SENTINEL`.
+fn parse_vitals(output: &str) -> Result<Vitals, AppError> {
+    let (loadavg, meminfo) = output.split_once(VITALS_SENTINEL).ok_or_else(|| {
+        AppError::ParseFailed("Unexpected vitals output: sentinel line missing".to_string())
+    })?;
+
+    let loads: Vec<f64> = loadavg
+        .split_whitespace()
+        .take(3)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    let [load_1m, load_5m, load_15m] = loads[..] else {
+        return Err(AppError::ParseFailed(format!(
+            "Could not parse /proc/loadavg: {}",
+            loadavg.trim()
+        )));
+    };
+
+    let meminfo_kb = |key: &str| -> Result<u64, AppError> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+            .ok_or_else(|| AppError::ParseFailed(format!("{} missing from /proc/meminfo", key)))
+    };
+
+    Ok(Vitals {
+        load_1m,
+        load_5m,
+        load_15m,
+        mem_total_kb: meminfo_kb("MemTotal")?,
+        mem_available_kb: meminfo_kb("MemAvailable")?,
+    })
+}
+
+/// Removes terminal control codes from remote output: CSI sequences (colors, cursor
+/// movement), OSC sequences such as window titles (ended by BEL or `ESC \`), and `\r`.
+fn strip_ansi(text: &str) -> String {
+    static ANSI_REGEX: OnceLock<Regex> = OnceLock::new();
+    let re = ANSI_REGEX.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\r").unwrap()
+    });
+    re.replace_all(text, "").into_owned()
+}
+
+/// Wraps a ROS command in an interactive bash so the user's ROS environment (.bashrc) is loaded.
+fn ros_shell_command(command: &str) -> String {
+    format!("bash -i -c {}", shell_quote(command))
+}
+
+/// Turns `env` into an `export KEY='value' ...; ` prefix, sorted by key.
+/// Keys must look like `[A-Z_][A-Z0-9_]*`; values are single-quoted. Empty when `env` is.
+fn env_exports(env: &HashMap<String, String>) -> Result<String, AppError> {
+    if env.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut keys: Vec<&String> = env.keys().collect();
+    keys.sort();
+    let mut exports = Vec::new();
+    for key in keys {
+        let is_valid = key
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c.is_ascii_uppercase() || c == '_' || (i > 0 && c.is_ascii_digit()));
+        if key.is_empty() || !is_valid {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid environment variable name: {:?}",
+                key
+            )));
+        }
+        exports.push(format!("{}={}", key, shell_quote(&env[key])));
+    }
+    Ok(format!("export {}; ", exports.join(" ")))
+}
+
+/// Runs `command` inside the robot's ROS container via `ros2_start`, from an interactive shell.
+fn ros2_start_command(command: &str) -> String {
+    ros_shell_command(&format!(
+        "ros2_start -- bash -i -c {}",
+        shell_quote(command)
+    ))
+}
+
+/// Expands a leading `~/` (or `~\` on Windows) in a local path against the home directory.
+/// The path is returned unchanged when there is no such prefix or no home is known.
+fn expand_home(path: &str) -> String {
+    let home_var = if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    };
+    let rest = path.strip_prefix("~/").or_else(|| {
+        path.strip_prefix("~\\")
+            .filter(|_| cfg!(target_os = "windows"))
+    });
+    match (rest, std::env::var_os(home_var)) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest).display().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Quotes a remote path for the remote shell while still letting a leading `~/` expand.
+fn remote_path_arg(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", shell_quote(rest)),
+        None => shell_quote(path),
+    }
+}
+
+/// Rejects remote paths containing shell metacharacters or control characters.
+/// The path is quoted anyway; this keeps obviously malformed input from reaching ssh.
+fn validate_remote_path(path: &str) -> Result<(), AppError> {
+    const FORBIDDEN: &str = ";&|$`<>(){}[]*?!'\"\\";
+    let is_bad = path.is_empty()
+        || path
+            .chars()
+            .any(|c| c.is_control() || FORBIDDEN.contains(c));
+    if is_bad {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid remote path: {:?}",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// Quotes `arg` as a single POSIX shell word, so quotes, `$`, `;` and backticks stay literal.
+/// Embedded single quotes are written as `'\''`.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Leaves plain words such as flags and numbers untouched and quotes everything else.
+fn quote_if_needed(arg: &str, posix_shell: bool) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_=.,:/@%+".contains(c));
+    if is_plain {
+        arg.to_string()
+    } else {
+        quote_for_shell(arg, posix_shell)
+    }
+}
+
+/// Quotes `arg` for the shell that runs the terminal command line.
+/// Without a POSIX shell (Windows), wt.exe hands the line straight to ssh.exe or wsl.exe,
+/// whose argv parser understands `\"` inside double quotes; no cmd.exe is involved.
+fn quote_for_shell(arg: &str, posix_shell: bool) -> String {
+    if posix_shell {
+        shell_quote(arg)
+    } else {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    }
+}
+
+/// Accepts IPv4/IPv6 literals and RFC 1123 hostnames (dot-separated labels of
+/// letters, digits and inner `-`). `_` is also allowed because the robots are
+/// reached through ssh config aliases such as `kyubic_main`. Link-local IPv6
+/// literals may carry a `%<iface>` zone (`fe80::1%eth0`), which std can't parse.
+/// Whitespace and shell metacharacters never pass, so targets are safe to hand to ping/ssh.
+fn validate_target(target: &str) -> Result<(), AppError> {
+    let invalid =
+        || AppError::InvalidInput(format!("Invalid host name or IP address: {:?}", target));
+
+    if let Some((addr, zone)) = target.split_once('%') {
+        let valid_zone = !zone.is_empty()
+            && zone
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        return if valid_zone && addr.parse::<Ipv6Addr>().is_ok() {
+            Ok(())
+        } else {
+            Err(invalid())
+        };
+    }
+    if target.parse::<IpAddr>().is_ok() {
+        return Ok(());
+    }
+
+    let valid_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if target.len() <= 253 && target.split('.').all(valid_label) {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Accepts login names made of letters, digits, `.`, `_` and inner `-`, so a
+/// `user@host` destination can never carry shell syntax.
+fn validate_username(username: &str) -> Result<(), AppError> {
+    let is_valid = !username.is_empty()
+        && !username.starts_with('-')
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+    if !is_valid {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid ssh username: {:?}",
+            username
+        )));
+    }
+    Ok(())
+}
+
+/// Checks a `-J` value: comma-separated hops of `[user@]host[:port]`, where an
+/// IPv6 host with a port is written `[addr]:port`.
+fn validate_jump_host(jump_host: &str) -> Result<(), AppError> {
+    let invalid = || AppError::InvalidInput(format!("Invalid jump host: {:?}", jump_host));
+
+    for hop in jump_host.split(',') {
+        let host_port = match hop.split_once('@') {
+            Some((user, rest)) => {
+                validate_username(user)?;
+                rest
+            }
+            None => hop,
+        };
+        let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+            let (host, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+            match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if rest.is_empty() => (host, None),
+                None => return Err(invalid()),
+            }
+        } else {
+            match host_port.split_once(':') {
+                // More than one colon is a bare IPv6 literal without a port
+                Some((host, port)) if !port.contains(':') => (host, Some(port)),
+                _ => (host_port, None),
+            }
+        };
+        if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+            return Err(invalid());
+        }
+        validate_target(host).map_err(|_| invalid())?;
+    }
+    Ok(())
+}
+
+/// Rejects options that would only fail once ssh is running inside the spawned terminal.
+fn validate_ssh_options(opts: &SshOptions) -> Result<(), AppError> {
+    if let Some(identity_file) = &opts.identity_file {
+        if identity_file.contains('"') {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid identity file path: {}",
+                identity_file
+            )));
+        }
+        // Paths inside WSL can't be checked from the Windows side
+        if !runs_in_wsl(opts) && !Path::new(&expand_home(identity_file)).is_file() {
+            return Err(AppError::NotFound(format!(
+                "Identity file not found: {}",
+                identity_file
+            )));
+        }
+    }
+    if let Some(username) = opts.username.as_deref().filter(|u| !u.is_empty()) {
+        validate_username(username)?;
+    }
+    if let Some(jump_host) = &opts.jump_host {
+        validate_jump_host(jump_host)?;
+    }
+    if let Some(distro) = opts.wsl_distro.as_deref().filter(|d| !d.is_empty()) {
+        // Lands unquoted in the wsl.exe -d argument
+        let is_valid = !distro.starts_with('-')
+            && distro
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+        if !is_valid {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid WSL distribution name: {:?}",
+                distro
+            )));
+        }
+    }
+    validate_extra_ssh_opts(&opts.extra_ssh_opts)?;
+
+    Ok(())
+}
+
+/// Checks a systemd unit name only uses plain unit-name characters (no `\` escapes),
+/// so it can be put on the remote command line unquoted.
+fn validate_unit_name(unit: &str) -> Result<(), AppError> {
+    let is_safe = !unit.is_empty()
+        && !unit.starts_with('-')
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.@".contains(c));
+    if !is_safe {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid systemd unit name: {:?}",
+            unit
+        )));
+    }
+    Ok(())
+}
+
+/// Checks `extra_ssh_opts` is a list of `<flag> <value>` pairs using only `-o`, `-p`
+/// and `-i`, with values free of whitespace and shell metacharacters. `-o` only takes
+/// the connection-tuning keys below: anything else could load code or run commands
+/// (ProxyCommand, PKCS11Provider, RemoteCommand, ...).
+fn validate_extra_ssh_opts(extra: &[String]) -> Result<(), AppError> {
+    const ALLOWED_FLAGS: [&str; 3] = ["-o", "-p", "-i"];
+    const ALLOWED_OPTIONS: [&str; 17] = [
+        "addressfamily",
+        "ciphers",
+        "compression",
+        "connectionattempts",
+        "connecttimeout",
+        "hostkeyalgorithms",
+        "identitiesonly",
+        "ipqos",
+        "kexalgorithms",
+        "loglevel",
+        "macs",
+        "preferredauthentications",
+        "pubkeyacceptedalgorithms",
+        "pubkeyauthentication",
+        "serveralivecountmax",
+        "serveraliveinterval",
+        "tcpkeepalive",
+    ];
+    let invalid =
+        |reason: String| AppError::InvalidInput(format!("Invalid extra ssh option: {}", reason));
+
+    if !extra.len().is_multiple_of(2) {
+        return Err(invalid("each flag needs a value".to_string()));
+    }
+    for pair in extra.chunks(2) {
+        let (flag, value) = (pair[0].as_str(), pair[1].as_str());
+        if !ALLOWED_FLAGS.contains(&flag) {
+            return Err(invalid(format!("{} is not one of -o, -p, -i", flag)));
+        }
+        let is_plain = !value.is_empty()
+            && !value.starts_with('-')
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_=.,:/@%+~".contains(c));
+        if !is_plain {
+            return Err(invalid(format!("{} {:?}", flag, value)));
+        }
+        if flag == "-o" {
+            let key = value
+                .split('=')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if !value.contains('=') || !ALLOWED_OPTIONS.contains(&key.as_str()) {
+                return Err(invalid(format!("-o {}", value)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns `user@hostname` when a username is set, otherwise the bare hostname.
+fn ssh_destination(hostname: &str, opts: &SshOptions) -> String {
+    match &opts.username {
+        Some(user) if !user.is_empty() => format!("{}@{}", user, hostname),
+        _ => hostname.to_string(),
+    }
+}
+
+/// Translates `SshOptions` into raw ssh command-line flags (unquoted).
+fn ssh_option_args(opts: &SshOptions) -> Vec<String> {
+    let settings = settings();
+    let mut args = Vec::new();
+    let default_port = Some(settings.ssh_port).filter(|&port| port != DEFAULT_SSH_PORT);
+    if let Some(port) = opts.port.or(default_port) {
+        args.extend(["-p".to_string(), port.to_string()]);
+    }
+    if let Some(identity_file) = &opts.identity_file {
+        // ssh inside WSL expands `~` against the Linux home itself
+        let identity_file = if runs_in_wsl(opts) {
+            identity_file.clone()
+        } else {
+            expand_home(identity_file)
+        };
+        args.extend(["-i".to_string(), identity_file]);
+    }
+    if let Some(jump_host) = &opts.jump_host {
+        args.extend(["-J".to_string(), jump_host.clone()]);
+    }
+    let connect_timeout = opts
+        .connect_timeout_secs
+        .unwrap_or(settings.ssh_connect_timeout_secs);
+    args.extend([
+        "-o".to_string(),
+        format!("ConnectTimeout={}", connect_timeout),
+    ]);
+    match opts.host_key_policy {
+        HostKeyPolicy::Strict => {}
+        HostKeyPolicy::AcceptNew => args.extend([
+            "-o".to_string(),
+            "StrictHostKeyChecking=accept-new".to_string(),
+            "-o".to_string(),
+            "UpdateHostKeys=yes".to_string(),
+        ]),
+        HostKeyPolicy::Off => {
+            // Keep the throwaway keys out of known_hosts; WSL runs ssh on Linux paths
+            let null_file = if cfg!(windows) && !opts.wsl {
+                "NUL"
+            } else {
+                "/dev/null"
+            };
+            args.extend([
+                "-o".to_string(),
+                "StrictHostKeyChecking=no".to_string(),
+                "-o".to_string(),
+                format!("UserKnownHostsFile={}", null_file),
+            ]);
+        }
+    }
+    if opts.multiplex && multiplex_supported(opts) {
+        args.extend([
+            "-o".to_string(),
+            "ControlMaster=auto".to_string(),
+            "-o".to_string(),
+            "ControlPersist=60".to_string(),
+            "-o".to_string(),
+            format!("ControlPath={}", ssh_control_path(opts)),
+        ]);
+    }
+    args.extend(opts.extra_ssh_opts.iter().cloned());
+
+    args
+}
+
+/// True when the ssh that will run supports ControlMaster; Windows' OpenSSH doesn't.
+fn multiplex_supported(opts: &SshOptions) -> bool {
+    !cfg!(target_os = "windows") || runs_in_wsl(opts)
+}
+
+/// Returns the ControlMaster socket path pattern, one socket per user, host and port.
+/// `%C` is ssh's hash of those, which keeps long hosts within the 104-byte socket path
+/// limit (macOS's temp dir alone is ~50). Under WSL the path has to be on the Linux side.
+fn ssh_control_path(opts: &SshOptions) -> String {
+    const SOCKET_NAME: &str = "kyubic-ssh-%C";
+    if runs_in_wsl(opts) {
+        format!("/tmp/{}", SOCKET_NAME)
+    } else {
+        std::env::temp_dir().join(SOCKET_NAME).display().to_string()
+    }
+}
+
+/// Rejects payload sizes that some platform's ping would refuse.
+fn validate_ping_options(opts: &PingOptions) -> Result<(), AppError> {
+    match opts.size_bytes {
+        Some(size) if size > MAX_PING_SIZE_BYTES => Err(AppError::InvalidInput(format!(
+            "Ping size must be at most {} bytes, got {}",
+            MAX_PING_SIZE_BYTES, size
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Helper function to execute a ping command with a timeout.
+/// Tells a missing `ping` binary apart from an unreachable host.
+/// An offline result is retried up to `retries` more times before it is reported.
+#[instrument(level = "debug")]
+fn check_ping(target: &str, timeout_ms: u32, retries: u32) -> PingState {
+    let mut state = ping_once(target, timeout_ms);
+    for _ in 0..retries {
+        if state != PingState::Offline {
+            break;
+        }
+        std::thread::sleep(PING_RETRY_DELAY);
+        state = ping_once(target, timeout_ms);
+    }
+    state
+}
+
+/// Sends a single echo request to `target`.
+fn ping_once(target: &str, timeout_ms: u32) -> PingState {
+    match ping_command(target, 1, timeout_ms, &PingOptions::default()).status() {
+        Ok(status) if status.success() => PingState::Online,
+        Ok(_) => PingState::Offline,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            warn!("ping binary not found");
+            PingState::ToolMissing
+        }
+        Err(e) => {
+            warn!(error = %e, "failed to run ping");
+            PingState::Offline
+        }
+    }
+}
+
+/// Resolves `hostname` through the system resolver into its unique IP strings.
+/// IP literals come back unchanged.
+fn resolve_ips(hostname: &str) -> Result<Vec<String>, AppError> {
+    // The port is required by ToSocketAddrs but irrelevant to the lookup
+    let addrs = (hostname, 0)
+        .to_socket_addrs()
+        .map_err(|e| AppError::ResolveFailed(format!("Could not resolve {}: {}", hostname, e)))?;
+
+    let mut ips: Vec<String> = Vec::new();
+    for ip in addrs.map(|addr| addr.ip().to_string()) {
+        if !ips.contains(&ip) {
+            ips.push(ip);
+        }
+    }
+    if ips.is_empty() {
+        return Err(AppError::ResolveFailed(format!(
+            "{} resolved to no addresses",
+            hostname
+        )));
+    }
+    Ok(ips)
+}
+
+/// Pings `target` and returns the round-trip time, or `None` if it didn't answer.
+/// A reply whose time can't be read is a `ParseFailed` error, not `None`.
+async fn ping_latency(target: &str, retries: u32) -> Result<Option<f64>, AppError> {
+    match ping_reply(target, retries).await? {
+        Some(stdout) => reply_latency(target, &stdout).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Reads the round-trip time out of a successful ping's stdout.
+fn reply_latency(target: &str, stdout: &str) -> Result<f64, AppError> {
+    parse_ping_rtt(stdout).ok_or_else(|| {
+        AppError::ParseFailed(format!(
+            "{} replied but ping printed no round-trip time",
+            target
+        ))
+    })
+}
+
+/// Pings `target` once and returns ping's stdout if it got a reply, or `None` if it didn't.
+/// A missed reply is retried up to `retries` more times.
+async fn ping_reply(target: &str, retries: u32) -> Result<Option<String>, AppError> {
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            tokio::time::sleep(PING_RETRY_DELAY).await;
+        }
+        let ping = ping_command(
+            target,
+            1,
+            settings().ping_timeout_ms,
+            &PingOptions::default(),
+        );
+        let output = tokio::process::Command::from(ping)
+            .output()
+            .await
+            .map_err(|e| AppError::spawn("ping", e))?;
+
+        if output.status.success() {
+            return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Long-running children (streaming ssh) that must not outlive the app.
+fn child_registry() -> &'static Mutex<HashMap<u64, tokio::process::Child>> {
+    static CHILD_REGISTRY: OnceLock<Mutex<HashMap<u64, tokio::process::Child>>> = OnceLock::new();
+    CHILD_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Hands `child` to the registry and returns the id to kill it with later.
+fn register_child(child: tokio::process::Child) -> u64 {
+    static NEXT_CHILD_ID: AtomicU64 = AtomicU64::new(0);
+    let id = NEXT_CHILD_ID.fetch_add(1, Ordering::Relaxed);
+    child_registry().lock().unwrap().insert(id, child);
+    id
+}
+
+/// Takes the child out of the registry and kills it.
+/// A child that already exited is fine: the kill error is ignored.
+fn kill_child(id: u64) {
+    if let Some(mut child) = child_registry().lock().unwrap().remove(&id) {
+        let _ = child.start_kill();
+    }
+}
+
+/// Kills every child still registered; run when the app exits.
+fn kill_all_children() {
+    for (_, mut child) in child_registry().lock().unwrap().drain() {
+        let _ = child.start_kill();
+    }
+}
+
+/// Pings `poll_target` every few seconds until it stops answering or `timeout_secs`
+/// runs out, then emits `host-shutdown-confirmed` with the outcome.
+fn spawn_shutdown_confirmation(
+    app: AppHandle,
+    hostname: String,
+    poll_target: String,
+    timeout_secs: u32,
+) {
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let deadline = Duration::from_secs(u64::from(timeout_secs));
+        let confirmed = loop {
+            tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+            let target = poll_target.clone();
+            // Retry so one dropped echo isn't mistaken for the host powering off
+            let state = tokio::task::spawn_blocking(move || {
+                check_ping(&target, settings().ping_timeout_ms, BATCH_PING_RETRIES)
+            })
+            .await
+            .unwrap_or(PingState::Online);
+            if state == PingState::Offline {
+                break true;
+            }
+            if started.elapsed() >= deadline {
+                break false;
+            }
+        };
+
+        let event = ShutdownConfirmedEvent {
+            hostname,
+            confirmed,
+            elapsed_secs: started.elapsed().as_secs(),
+        };
+        if let Err(e) = app.emit("host-shutdown-confirmed", event) {
+            warn!(error = %e, "failed to emit host-shutdown-confirmed");
+        }
+    });
+}
+
+/// Running host monitors by id, aborted by stop_host_monitor or on exit.
+fn monitor_registry() -> &'static Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>> {
+    static MONITOR_REGISTRY: OnceLock<
+        Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>,
+    > = OnceLock::new();
+    MONITOR_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Stops every host monitor still running; run when the app exits.
+fn stop_all_monitors() {
+    for (_, task) in monitor_registry().lock().unwrap().drain() {
+        task.abort();
+    }
+}
+
+/// Expands an IPv4 CIDR such as `192.168.1.0/24` into its host addresses.
+fn expand_cidr(cidr: &str) -> Result<Vec<Ipv4Addr>, AppError> {
+    let (_, _, hosts) = parse_cidr(cidr)?;
+    Ok(hosts.map(Ipv4Addr::from).collect())
+}
+
+/// Parses an IPv4 CIDR into its network address, prefix length and host range.
+/// The network and broadcast addresses are excluded from the range except for /31 and /32.
+/// Prefixes shorter than /16 are rejected to avoid accidental huge scans.
+fn parse_cidr(cidr: &str) -> Result<(Ipv4Addr, u32, RangeInclusive<u32>), AppError> {
+    let invalid = || AppError::InvalidInput(format!("Invalid IPv4 CIDR: {}", cidr));
+    let (addr, prefix) = cidr.trim().split_once('/').ok_or_else(invalid)?;
+    let addr: Ipv4Addr = addr.parse().map_err(|_| invalid())?;
+    let prefix: u32 = prefix.parse().map_err(|_| invalid())?;
+    if prefix > 32 {
+        return Err(invalid());
+    }
+    if prefix < 16 {
+        return Err(AppError::InvalidInput(format!(
+            "Refusing to scan /{}; use /16 or narrower",
+            prefix
+        )));
+    }
+
+    let mask = u32::MAX << (32 - prefix);
+    let network = u32::from(addr) & mask;
+    let broadcast = network | !mask;
+    let hosts = if prefix >= 31 {
+        network..=broadcast
+    } else {
+        network + 1..=broadcast - 1
+    };
+    Ok((Ipv4Addr::from(network), prefix, hosts))
+}
+
+/// Reports whether `binary` is on PATH and where.
+fn prereq_status(binary: &str) -> PrereqStatus {
+    let path = find_in_path(binary);
+    PrereqStatus {
+        name: binary.to_string(),
+        available: path.is_some(),
+        path: path.map(|path| path.display().to_string()),
+    }
+}
+
+/// Looks `binary` up on PATH the way the shell would (with `.exe` on Windows).
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(target_os = "windows") {
+        format!("{}.exe", binary)
+    } else {
+        binary.to_string()
+    };
+    // symlink_metadata so Windows app execution aliases such as wt.exe, which
+    // can't be followed, still count as present
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.symlink_metadata().is_ok_and(|meta| !meta.is_dir()))
+}
+
+/// Settings the commands fall back on when an argument is omitted.
+/// Filled at startup and kept in step by load_settings/save_settings.
+fn app_settings() -> &'static Mutex<AppSettings> {
+    static APP_SETTINGS: OnceLock<Mutex<AppSettings>> = OnceLock::new();
+    APP_SETTINGS.get_or_init(|| Mutex::new(AppSettings::default()))
 }
 
-#[tauri::command]
-fn open_ssh_terminal(
-    hostname: String,
-    ip: String,
-    run_ros: bool,
-    remote_command: String,
-) -> Result<(), String> {
-    // Detect Localhost
-    let is_local = ip == "127.0.0.1" || hostname == "localhost";
+/// A copy of the current settings, so no lock is held across an await.
+fn settings() -> AppSettings {
+    app_settings().lock().unwrap().clone()
+}
 
-    // Build the SSH command arguments
-    let shell_args = if is_local {
-        // Local Mode
-        if run_ros {
-            format!("bash -i -c '{}'", remote_command)
-        } else {
-            "echo 'Starting Local Terminal'".to_string()
-        }
-    } else {
-        // SSH Conection Mode
-        if run_ros {
-            format!("ssh -t {} \"bash -i -c '{}'\"", hostname, remote_command)
-        } else {
-            format!("ssh {}", hostname)
-        }
-    };
+/// The emulator named in `KYUBIC_TERMINAL`, otherwise the one chosen in the settings.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn preferred_terminal() -> Option<String> {
+    std::env::var("KYUBIC_TERMINAL")
+        .ok()
+        .or_else(|| settings().terminal)
+        .filter(|name| !name.trim().is_empty())
+}
 
-    // Open in a new tab
-    launch_terminal(&shell_args, WindowMode::Tab)
+/// Shared store of the most recent ping result per target.
+fn ping_cache() -> &'static Mutex<HashMap<String, (Instant, PingState)>> {
+    static PING_CACHE: OnceLock<Mutex<HashMap<String, (Instant, PingState)>>> = OnceLock::new();
+    PING_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-#[tauri::command]
-fn exec_shutdown_command(hostname: String) -> Result<(), String> {
-    let ssh_args = format!("ssh -t {} \"sudo shutdown -h now\"", hostname);
+/// Returns the cached state for `target` if it is younger than `ttl`.
+/// Entries older than `ttl` are evicted on the way so the map doesn't grow unbounded.
+fn cached_ping(target: &str, ttl: Duration) -> Option<PingState> {
+    let mut cache = ping_cache().lock().unwrap();
+    cache.retain(|_, (checked_at, _)| checked_at.elapsed() < ttl);
+    cache.get(target).map(|(_, state)| *state)
+}
 
-    // Open in a new independent window to avoid cluttering the main workflow
-    launch_terminal(&ssh_args, WindowMode::NewWindow)
+/// Helper function to check whether a TCP handshake with `host:port` succeeds.
+/// Useful for hosts that drop ICMP. Each resolved address is tried in turn.
+fn check_tcp(host: &str, port: u16, timeout: Duration) -> bool {
+    let Ok(addrs) = (host, port).to_socket_addrs() else {
+        return false;
+    };
+
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, timeout).is_ok())
 }
 
-// =========================================
-// 3. Core Logic Helpers
-// =========================================
+/// Builds a ping command sending `count` echo requests for the current OS.
+fn ping_command(target: &str, count: u32, timeout_ms: u32, opts: &PingOptions) -> Command {
+    let (program, args) = ping_args(target, count, timeout_ms, opts);
+    let mut cmd = Command::new(program);
+    cmd.args(args);
 
-/// Helper function to execute a ping command with a timeout.
-fn check_ping(target: &str) -> bool {
     #[cfg(target_os = "windows")]
     {
         use std::os::windows::process::CommandExt;
         const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
 
-        Command::new("ping")
-            .args(["-n", "1", target])
-            .args(["-w", "1000"]) // 1000ms timeout
-            .creation_flags(CREATE_NO_WINDOW)
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+    debug!(?cmd, "built command");
+    cmd
+}
+
+/// Returns the ping program and its arguments for `count` echoes to `target`.
+/// IPv6 literals (anything containing a colon, including `fe80::1%eth0` zone
+/// suffixes) use `ping6` on Unix and `ping -6` on Windows.
+/// `timeout_ms` is passed as-is on Windows and macOS, and rounded up to whole seconds on Linux.
+/// macOS `ping6` has no wait option (its `-W` is a node information query), so it gets none.
+fn ping_args(
+    target: &str,
+    count: u32,
+    timeout_ms: u32,
+    opts: &PingOptions,
+) -> (&'static str, Vec<String>) {
+    let is_ipv6 = target.contains(':');
+    let count = count.to_string();
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut args = Vec::new();
+        if is_ipv6 {
+            args.push("-6".to_string());
+        }
+        if let Some(size) = opts.size_bytes {
+            args.extend(["-l".to_string(), size.to_string()]);
+        }
+        if opts.dont_fragment {
+            args.push("-f".to_string());
+        }
+        args.extend(["-n", &count, target].map(String::from));
+        args.extend(["-w".to_string(), timeout_ms.to_string()]);
+        ("ping", args)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        Command::new("ping")
-            .args(["-c", "1", target])
-            .args(["-W", "1"]) // 1s timeout
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+        let program = if is_ipv6 { "ping6" } else { "ping" };
+        // Linux's -W takes whole seconds, macOS's takes milliseconds
+        let wait = if cfg!(target_os = "macos") {
+            timeout_ms
+        } else {
+            timeout_ms.div_ceil(1000)
+        };
+        let mut args = Vec::new();
+        if let Some(size) = opts.size_bytes {
+            args.extend(["-s".to_string(), size.to_string()]);
+        }
+        if opts.dont_fragment {
+            if cfg!(target_os = "macos") {
+                args.push("-D".to_string());
+            } else {
+                args.extend(["-M".to_string(), "do".to_string()]);
+            }
+        }
+        // BSD getopt stops at the first operand, so every flag has to precede the target
+        args.extend(["-c".to_string(), count]);
+        if !(cfg!(target_os = "macos") && is_ipv6) {
+            args.extend(["-W".to_string(), wait.to_string()]);
+        }
+        args.push(target.to_string());
+        (program, args)
     }
 }
 
+/// Extracts the round-trip time in milliseconds from ping output.
+/// Handles `time=12.3 ms` (Linux/macOS) as well as `time=12ms` / `time<1ms` (Windows).
+/// The word before `=` is not matched, since localized Windows prints e.g. `時間 =1ms`.
+fn parse_ping_rtt(output: &str) -> Option<f64> {
+    static RTT_REGEX: OnceLock<Regex> = OnceLock::new();
+    let re = RTT_REGEX.get_or_init(|| Regex::new(r"[=<]\s*([0-9]+(?:\.[0-9]+)?)\s*ms\b").unwrap());
+
+    re.captures(output)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+/// Availability of the platform's terminal launcher.
+fn terminal_prereq_status() -> PrereqStatus {
+    #[cfg(target_os = "windows")]
+    return prereq_status("wt");
+
+    #[cfg(target_os = "macos")]
+    return prereq_status("osascript");
+
+    #[cfg(target_os = "linux")]
+    return linux_terminal_prereq_status();
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return PrereqStatus {
+        name: "terminal".to_string(),
+        available: false,
+        path: None,
+    };
+}
+
 /// Common entry point to launch the terminal based on the OS.
-fn launch_terminal(ssh_args: &str, mode: WindowMode) -> Result<(), String> {
+/// Returns the PID of the process spawned locally. That is the terminal itself for
+/// xterm/Alacritty/Konsole, but only a launcher for wt, gnome-terminal (which hands
+/// off to its server) and macOS, where it is the short-lived `osascript`.
+fn launch_terminal(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<u32, AppError> {
+    debug!(ssh_args, ?mode, ?term_opts, "launching terminal");
+
+    #[cfg(target_os = "windows")]
+    return launch_on_windows(ssh_args, mode, term_opts);
+
+    #[cfg(target_os = "macos")]
+    return launch_on_macos(ssh_args, mode, term_opts);
+
+    #[cfg(target_os = "linux")]
+    return launch_on_linux(ssh_args, mode, term_opts);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return Err(AppError::TerminalLaunch("Unsupported OS".to_string()));
+}
+
+/// Builds, without spawning, the launcher process launch_terminal starts first for
+/// `ssh_args`: the wt, osascript or terminal emulator call with all of its wrapping.
+fn terminal_command(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<Command, AppError> {
     #[cfg(target_os = "windows")]
-    return launch_on_windows(ssh_args, mode);
+    return Ok(windows_terminal_command(ssh_args, mode, term_opts));
 
     #[cfg(target_os = "macos")]
-    return launch_on_macos(ssh_args, mode);
+    return select_mac_terminal()
+        .map(|app| osascript_command(&macos_script(app, ssh_args, mode, term_opts)));
 
     #[cfg(target_os = "linux")]
-    return launch_on_linux(ssh_args, mode);
+    return linux_terminal_candidates().map(|candidates| {
+        // launch_on_linux moves past emulators that fail to spawn, i.e. aren't installed
+        let emulator = candidates
+            .iter()
+            .copied()
+            .find(|emulator| find_in_path(emulator.binary()).is_some())
+            .unwrap_or(candidates[0]);
+        linux_terminal_command(emulator, ssh_args, mode, term_opts)
+    });
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    return Err("Unsupported OS".to_string());
+    return Err(AppError::TerminalLaunch("Unsupported OS".to_string()));
+}
+
+/// Parses the summary block printed at the end of a ping run.
+/// Supports the Linux/macOS `N packets transmitted, M received` + `min/avg/max` lines
+/// and the Windows `Sent = N, Received = M` + `Minimum = Xms, ..., Average = Zms` lines.
+/// Windows fields are matched by position, since localized builds translate the labels
+/// (`送信 = 4、受信 = 4`, `平均 = 1ms`).
+fn parse_ping_statistics(output: &str) -> Option<LinkQuality> {
+    static COUNT_REGEX: OnceLock<Regex> = OnceLock::new();
+    static AVG_REGEX: OnceLock<Regex> = OnceLock::new();
+    let count_re = COUNT_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?:(\d+) packets transmitted, (\d+) (?:packets )?received|= (\d+)[,、]\s*\S+ = (\d+))",
+        )
+        .unwrap()
+    });
+    let avg_re = AVG_REGEX.get_or_init(|| {
+        Regex::new(r"(?:= [0-9.]+/([0-9.]+)/|= \d+ms[,、]\s*\S+ = \d+ms[,、]\s*\S+ = (\d+)ms)")
+            .unwrap()
+    });
+
+    let caps = count_re.captures(output)?;
+    let sent: u32 = caps.get(1).or(caps.get(3))?.as_str().parse().ok()?;
+    let received: u32 = caps.get(2).or(caps.get(4))?.as_str().parse().ok()?;
+    if sent == 0 {
+        return None;
+    }
+
+    let avg_rtt_ms = avg_re
+        .captures(output)
+        .and_then(|caps| caps.get(1).or(caps.get(2)))
+        .and_then(|m| m.as_str().parse().ok());
+
+    Some(LinkQuality {
+        sent,
+        received,
+        loss_percent: f64::from(sent - received.min(sent)) * 100.0 / f64::from(sent),
+        avg_rtt_ms,
+    })
 }
 
 // =========================================
 // 4. OS-Specific Implementations
 // =========================================
 
+/// wt.exe splits its command line into subcommands at `;`, so a literal one has to be `\;`.
+#[cfg(target_os = "windows")]
+fn wt_escape(line: &str) -> String {
+    line.replace(';', "\\;")
+}
+
+/// `wt` opening `ssh_args` as a tab, window or pane.
+/// A profile given with `-p` only lends its colours, font and starting directory: wt
+/// replaces the profile's own command line with ours, so a WSL or PowerShell profile
+/// still runs Windows' ssh.exe. Set `SshOptions.wsl` to run ssh inside WSL instead.
 #[cfg(target_os = "windows")]
-fn launch_on_windows(ssh_args: &str, mode: WindowMode) -> Result<(), String> {
-    // -w 0: Open in the current window (New Tab)
+fn windows_terminal_command(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Command {
+    // -w 0: Open in the current window (New Tab / Split Pane)
     // -w -1: Open in a new window
-    let window_flag = match mode {
-        WindowMode::Tab => "0",
-        WindowMode::NewWindow => "-1",
+    let (window_flag, subcommand) = match mode {
+        WindowMode::Tab => ("0", "new-tab"),
+        WindowMode::NewWindow => ("-1", "new-tab"),
+        WindowMode::SplitPane => ("0", "split-pane"),
     };
 
-    Command::new("wt")
-        .args(["-w", window_flag, "new-tab", "cmd", "/k", ssh_args])
+    let mut cmd = Command::new("wt");
+    cmd.args(["-w", window_flag, subcommand]);
+    if let Some(title) = &term_opts.title {
+        // Keep the shell from replacing our title with its own
+        cmd.args(["--title", title, "--suppressApplicationTitle"]);
+    }
+    if let Some(profile) = &term_opts.profile {
+        cmd.args(["-p", profile]);
+    }
+    // Run the line directly in the tab rather than under `cmd /k`: cmd.exe ignores `\"`
+    // and would act on `&`, `|`, `>` or `%VAR%` inside our quotes. raw_arg keeps the
+    // quoting intact for ssh.exe/wsl.exe; failed sessions stay open via closeOnExit.
+    use std::os::windows::process::CommandExt;
+    cmd.raw_arg(wt_escape(ssh_args));
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn launch_on_windows(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<u32, AppError> {
+    let mut cmd = windows_terminal_command(ssh_args, mode, term_opts);
+    debug!(?cmd, "spawning Windows Terminal");
+    let child = cmd.spawn().map_err(|e| {
+        AppError::TerminalLaunch(format!("Failed to launch Windows Terminal: {}", e))
+    })?;
+    Ok(child.id())
+}
+
+#[cfg(target_os = "macos")]
+fn launch_on_macos(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<u32, AppError> {
+    let app = select_mac_terminal()?;
+    let script = |mode| macos_script(app, ssh_args, mode, term_opts);
+
+    let needs_keystroke = matches!(app, MacTerminalApp::Terminal)
+        && matches!(mode, WindowMode::Tab | WindowMode::SplitPane);
+    match run_osascript(app, &script(mode)) {
+        // Cmd-T needs System Events access; a plain new window doesn't. A refusal stops
+        // the script before anything runs, but other failures may come after the command
+        // started in a tab, so they aren't retried.
+        Err(e @ AppError::PermissionDenied(_)) if needs_keystroke => {
+            warn!(error = %e, "Terminal tab failed, falling back to a new window");
+            run_osascript(app, &script(WindowMode::NewWindow))
+        }
+        result => result,
+    }
+}
+
+/// The AppleScript that runs `ssh_args` in `app`.
+#[cfg(target_os = "macos")]
+fn macos_script(
+    app: MacTerminalApp,
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> String {
+    // Hold the window open after a failure so the error stays readable
+    // (Linux does this with `exec bash`, Windows Terminal's closeOnExit on Windows).
+    let ssh_args = format!(
+        "{} || {{ echo; echo 'Command failed. Press enter to close.'; read; }}",
+        ssh_args
+    );
+    let ssh_args = applescript_escape(&ssh_args);
+    let title = term_opts.title.as_deref().map(applescript_escape);
+    match app {
+        MacTerminalApp::Terminal => terminal_app_script(
+            &ssh_args,
+            mode,
+            title.as_deref(),
+            term_opts.tab_delay_ms.unwrap_or(200),
+        ),
+        MacTerminalApp::ITerm2 => iterm2_script(&ssh_args, mode, title.as_deref()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn osascript_command(script: &str) -> Command {
+    let mut cmd = Command::new("osascript");
+    cmd.args(["-e", script]);
+    cmd
+}
+
+/// Runs `script` with osascript and waits for it, so AppleScript errors surface.
+/// Refused permissions come back as PermissionDenied, with instructions for the operator.
+#[cfg(target_os = "macos")]
+fn run_osascript(app: MacTerminalApp, script: &str) -> Result<u32, AppError> {
+    debug!(?app, script, "running osascript");
+    let child = osascript_command(script)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to launch Windows Terminal: {}", e))?;
-    Ok(())
+        .map_err(|e| AppError::TerminalLaunch(format!("Failed to launch {:?}: {}", app, e)))?;
+    let pid = child.id();
+    let output = child
+        .wait_with_output()
+        .map_err(|e| AppError::TerminalLaunch(format!("Failed to run osascript: {}", e)))?;
+    if output.status.success() {
+        return Ok(pid);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let hint = if stderr.contains("-1743") || stderr.contains("Not authorized to send Apple events")
+    {
+        "Allow this app to control it under System Settings > Privacy & Security > Automation"
+    } else if stderr.contains("-25211")
+        || stderr.contains("-1719")
+        || stderr.contains("assistive access")
+        || stderr.contains("not allowed to send keystrokes")
+    {
+        "Add this app under System Settings > Privacy & Security > Accessibility"
+    } else {
+        return Err(AppError::TerminalLaunch(format!(
+            "{:?} could not be scripted: {}",
+            app,
+            stderr.trim()
+        )));
+    };
+    Err(AppError::PermissionDenied(format!(
+        "macOS blocked scripting {:?}. {}, then try again. ({})",
+        app,
+        hint,
+        stderr.trim()
+    )))
+}
+
+/// Escapes `text` for embedding inside an AppleScript string literal.
+#[cfg(target_os = "macos")]
+fn applescript_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Picks the app from `KYUBIC_TERMINAL` or the settings, otherwise iTerm2 if it is
+/// running, else Terminal.
+#[cfg(target_os = "macos")]
+fn select_mac_terminal() -> Result<MacTerminalApp, AppError> {
+    if let Some(name) = preferred_terminal() {
+        return match name.trim().to_ascii_lowercase().as_str() {
+            "terminal" => Ok(MacTerminalApp::Terminal),
+            "iterm2" | "iterm" => Ok(MacTerminalApp::ITerm2),
+            _ => Err(AppError::InvalidInput(format!(
+                "Unsupported terminal: {}",
+                name
+            ))),
+        };
+    }
+
+    let iterm_running = Command::new("pgrep")
+        .args(["-x", "iTerm2"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    Ok(if iterm_running {
+        MacTerminalApp::ITerm2
+    } else {
+        MacTerminalApp::Terminal
+    })
 }
 
+/// Terminal.app has no scriptable "new tab", so Tab mode still sends Cmd-T, but it
+/// checks that a tab actually appeared, retries once, and otherwise opens a new
+/// window rather than running the command in whatever tab happens to be in front.
+/// A refused keystroke fails the script so launch_on_macos can report and fall back.
+/// `ssh_args` and `title` must already be AppleScript-escaped.
 #[cfg(target_os = "macos")]
-fn launch_on_macos(ssh_args: &str, mode: WindowMode) -> Result<(), String> {
-    let script = match mode {
-        WindowMode::Tab => format!(
+fn terminal_app_script(
+    ssh_args: &str,
+    mode: WindowMode,
+    title: Option<&str>,
+    tab_delay_ms: u32,
+) -> String {
+    let title_line = title
+        .map(|t| format!("set custom title of newTab to \"{}\"", t))
+        .unwrap_or_default();
+    match mode {
+        WindowMode::Tab | WindowMode::SplitPane => format!(
             "tell application \"Terminal\"
                 activate
-                try
-                    tell application \"System Events\" to keystroke \"t\" using command down
-                on error
-                end try
-                delay 0.2
-                do script \"{}\" in front window
+                if (count of windows) = 0 then
+                    set newTab to do script \"{cmd}\"
+                else
+                    set tabCount to count of tabs of front window
+                    repeat 2 times
+                        tell application \"System Events\" to keystroke \"t\" using command down
+                        delay {delay}
+                        if (count of tabs of front window) > tabCount then exit repeat
+                    end repeat
+                    if (count of tabs of front window) > tabCount then
+                        set newTab to do script \"{cmd}\" in selected tab of front window
+                    else
+                        set newTab to do script \"{cmd}\"
+                    end if
+                end if
+                {title}
             end tell",
-            ssh_args
+            cmd = ssh_args,
+            delay = f64::from(tab_delay_ms) / 1000.0,
+            title = title_line
         ),
         WindowMode::NewWindow => format!(
             "tell application \"Terminal\"
                 activate
-                do script \"{}\"
+                set newTab to do script \"{}\"
+                {}
             end tell",
-            ssh_args
+            ssh_args, title_line
         ),
-    };
+    }
+}
 
-    Command::new("osascript")
-        .args(["-e", &script])
-        .spawn()
-        .map_err(|e| format!("Failed to launch Terminal: {}", e))?;
-    Ok(())
+/// iTerm2 has native tab creation, so no keystroke simulation is needed.
+/// `ssh_args` and `title` must already be AppleScript-escaped.
+#[cfg(target_os = "macos")]
+fn iterm2_script(ssh_args: &str, mode: WindowMode, title: Option<&str>) -> String {
+    let title_line = title
+        .map(|t| format!("set name of newSession to \"{}\"", t))
+        .unwrap_or_default();
+    match mode {
+        WindowMode::Tab | WindowMode::SplitPane => format!(
+            "tell application \"iTerm2\"
+                activate
+                if (count of windows) = 0 then
+                    create window with default profile
+                else
+                    tell current window to create tab with default profile
+                end if
+                set newSession to current session of current window
+                tell newSession to write text \"{}\"
+                {}
+            end tell",
+            ssh_args, title_line
+        ),
+        WindowMode::NewWindow => format!(
+            "tell application \"iTerm2\"
+                activate
+                set newWindow to (create window with default profile)
+                set newSession to current session of newWindow
+                tell newSession to write text \"{}\"
+                {}
+            end tell",
+            ssh_args, title_line
+        ),
+    }
 }
 
 #[cfg(target_os = "linux")]
-fn launch_on_linux(ssh_args: &str, mode: WindowMode) -> Result<(), String> {
-    let flag = match mode {
-        WindowMode::Tab => "--tab",
-        WindowMode::NewWindow => "--window",
-    };
+fn launch_on_linux(
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Result<u32, AppError> {
+    // Try each candidate in turn and stop at the first one that spawns
+    let mut failures = Vec::new();
+    for emulator in linux_terminal_candidates()? {
+        let spawned = linux_terminal_command(emulator, ssh_args, mode, term_opts).spawn();
+
+        match spawned {
+            Ok(child) => return Ok(child.id()),
+            Err(e) => {
+                warn!(terminal = emulator.binary(), error = %e, "terminal failed to spawn");
+                failures.push(format!("{}: {}", emulator.binary(), e));
+            }
+        }
+    }
 
-    Command::new("gnome-terminal")
-        // Remove AppImage-specific environment variables to prevent conflicts
-        // with the system python (fixing "ModuleNotFoundError: encodings").
-        .env_remove("PYTHONHOME")
+    Err(AppError::TerminalLaunch(format!(
+        "Failed to launch a terminal ({})",
+        failures.join("; ")
+    )))
+}
+
+/// `emulator` running `ssh_args` under bash, dropping into a shell once it ends.
+#[cfg(target_os = "linux")]
+fn linux_terminal_command(
+    emulator: TerminalEmulator,
+    ssh_args: &str,
+    mode: WindowMode,
+    term_opts: &TerminalOptions,
+) -> Command {
+    let shell_command = format!("{}; exec bash", ssh_args);
+    let mut cmd = Command::new(emulator.binary());
+    // Remove AppImage-specific environment variables to prevent conflicts
+    // with the system python (fixing "ModuleNotFoundError: encodings").
+    cmd.env_remove("PYTHONHOME")
         .env_remove("PYTHONPATH")
         .env_remove("LD_LIBRARY_PATH")
         .env_remove("GIO_MODULE_DIR")
-        .args([
-            flag,
-            "--",
-            "bash",
-            "-c",
-            &format!("{}; exec bash", ssh_args),
-        ])
-        .spawn()
-        .map_err(|e| format!("Failed to launch gnome-terminal: {}", e))?;
-    Ok(())
+        .args(emulator.launch_args(&mode, term_opts.title.as_deref(), &shell_command));
+    cmd
+}
+
+/// First installed terminal candidate, or a missing entry naming all of them.
+#[cfg(target_os = "linux")]
+fn linux_terminal_prereq_status() -> PrereqStatus {
+    let binaries: Vec<&str> = linux_terminal_candidates()
+        .unwrap_or_default()
+        .into_iter()
+        .map(TerminalEmulator::binary)
+        .collect();
+    binaries
+        .iter()
+        .map(|binary| prereq_status(binary))
+        .find(|prereq| prereq.available)
+        .unwrap_or(PrereqStatus {
+            name: binaries.join("/"),
+            available: false,
+            path: None,
+        })
+}
+
+/// Terminals to try: the one named in `KYUBIC_TERMINAL` or the settings, or every
+/// supported one in order.
+#[cfg(target_os = "linux")]
+fn linux_terminal_candidates() -> Result<Vec<TerminalEmulator>, AppError> {
+    match preferred_terminal() {
+        Some(name) => TerminalEmulator::from_name(&name)
+            .map(|emulator| vec![emulator])
+            .ok_or_else(|| AppError::InvalidInput(format!("Unsupported terminal: {}", name))),
+        None => Ok(TerminalEmulator::ALL.to_vec()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl TerminalEmulator {
+    const ALL: [TerminalEmulator; 4] = [
+        TerminalEmulator::GnomeTerminal,
+        TerminalEmulator::Konsole,
+        TerminalEmulator::Xterm,
+        TerminalEmulator::Alacritty,
+    ];
+
+    fn binary(self) -> &'static str {
+        match self {
+            TerminalEmulator::GnomeTerminal => "gnome-terminal",
+            TerminalEmulator::Konsole => "konsole",
+            TerminalEmulator::Xterm => "xterm",
+            TerminalEmulator::Alacritty => "alacritty",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<TerminalEmulator> {
+        TerminalEmulator::ALL
+            .into_iter()
+            .find(|emulator| emulator.binary().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Arguments that open `shell_command` (run by bash) as a tab or window, optionally titled.
+    fn launch_args(
+        self,
+        mode: &WindowMode,
+        title: Option<&str>,
+        shell_command: &str,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = match (self, mode) {
+            (TerminalEmulator::GnomeTerminal, WindowMode::Tab | WindowMode::SplitPane) => {
+                vec!["--tab".into()]
+            }
+            (TerminalEmulator::GnomeTerminal, WindowMode::NewWindow) => vec!["--window".into()],
+            (TerminalEmulator::Konsole, WindowMode::Tab | WindowMode::SplitPane) => {
+                vec!["--new-tab".into()]
+            }
+            (TerminalEmulator::Konsole, WindowMode::NewWindow) => Vec::new(),
+            (TerminalEmulator::Xterm | TerminalEmulator::Alacritty, _) => Vec::new(),
+        };
+        if let Some(title) = title {
+            match self {
+                TerminalEmulator::GnomeTerminal => args.push(format!("--title={}", title)),
+                TerminalEmulator::Konsole => {
+                    args.extend(["-p".into(), format!("tabtitle={}", title)])
+                }
+                TerminalEmulator::Xterm => args.extend(["-T".into(), title.to_string()]),
+                TerminalEmulator::Alacritty => args.extend(["--title".into(), title.to_string()]),
+            }
+        }
+        let exec_flag = match self {
+            TerminalEmulator::GnomeTerminal => "--",
+            _ => "-e",
+        };
+        args.extend([
+            exec_flag.into(),
+            "bash".into(),
+            "-c".into(),
+            shell_command.to_string(),
+        ]);
+        args
+    }
 }
 
 // =========================================
@@ -187,13 +3475,745 @@ fn launch_on_linux(ssh_args: &str, mode: WindowMode) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Quiet by default; set KYUBIC_LOG (e.g. `debug`) to get argv and span logs
+    let filter = EnvFilter::try_from_env("KYUBIC_LOG").unwrap_or_else(|_| EnvFilter::new("warn"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             check_connection_status,
+            clear_ping_cache,
+            measure_latency,
+            check_batch_latency,
+            check_batch_detailed,
+            measure_link_quality,
+            ping_verbose,
+            resolve_host,
+            ping_with_resolution,
+            scan_subnet,
+            validate_cidr,
+            check_tcp_port,
+            start_host_monitor,
+            stop_host_monitor,
             open_ssh_terminal,
-            exec_shutdown_command
+            preview_ssh_terminal,
+            exec_shutdown_command,
+            preview_shutdown_command,
+            safe_shutdown,
+            preview_safe_shutdown,
+            exec_reboot_command,
+            preview_reboot_command,
+            restart_ros,
+            preview_restart_ros,
+            run_remote_command,
+            run_remote_command_batch,
+            test_ssh_auth,
+            measure_ssh_rtt,
+            fetch_ros_logs,
+            tail_journal,
+            read_remote_file,
+            write_remote_file,
+            get_disk_usage,
+            get_system_vitals,
+            check_clock_skew,
+            list_ros_topics,
+            echo_ros_topic,
+            close_ssh_multiplex,
+            check_local_prerequisites,
+            list_local_interfaces,
+            generate_diagnostics_bundle,
+            load_hosts_config,
+            open_in_file_manager,
+            load_settings,
+            save_settings,
+            get_host_preset,
+            save_host_preset
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .setup(|app| {
+            // Saved settings become the defaults before the frontend issues any command
+            match config_file_path(app.handle(), SETTINGS_FILE_NAME)
+                .and_then(|path| read_config_json::<AppSettings>(&path))
+            {
+                Ok(settings) => *app_settings().lock().unwrap() = settings,
+                Err(e) => warn!(error = %e, "using default settings"),
+            }
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Don't leave streaming ssh sessions or monitors running after the window is gone
+            if let tauri::RunEvent::Exit = event {
+                kill_all_children();
+                stop_all_monitors();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_target_accepts_hosts_and_ip_literals() {
+        for target in [
+            "192.168.9.100",
+            "localhost",
+            "kyubic_main",
+            "kyubic-jetson.local",
+            "::1",
+            "fe80::1",
+            "fe80::1%eth0",
+            "fe80::1%12",
+        ] {
+            assert!(
+                validate_target(target).is_ok(),
+                "{} should be accepted",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn validate_target_rejects_injection() {
+        for target in [
+            "",
+            "8.8.8.8; rm -rf",
+            "8.8.8.8 && id",
+            "$(x)",
+            "`id`",
+            "-oProxyCommand=touch /tmp/p",
+            "-v",
+            "host name",
+            "host\nname",
+            "fe80::1%eth0;id",
+            "fe80::1%",
+            "192.168.9.100%eth0",
+            "kyubic_main%eth0",
+        ] {
+            assert!(
+                validate_target(target).is_err(),
+                "{:?} should be rejected",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn validate_ssh_options_checks_user_jump_host_and_distro() {
+        let opts = |f: fn(&mut SshOptions)| {
+            let mut opts = SshOptions::default();
+            f(&mut opts);
+            opts
+        };
+        assert!(validate_ssh_options(&opts(|o| o.username = Some("rov".into()))).is_ok());
+        assert!(validate_ssh_options(&opts(|o| o.username = Some("x$(id)".into()))).is_err());
+        assert!(validate_ssh_options(&opts(|o| o.username = Some("a b".into()))).is_err());
+        assert!(validate_ssh_options(&opts(|o| o.username = Some("-oX".into()))).is_err());
+
+        for jump_host in [
+            "bastion",
+            "rov@bastion:2222",
+            "[fe80::1%eth0]:22",
+            "a,b@c:22",
+        ] {
+            let opts = SshOptions {
+                jump_host: Some(jump_host.into()),
+                ..Default::default()
+            };
+            assert!(
+                validate_ssh_options(&opts).is_ok(),
+                "{} should be accepted",
+                jump_host
+            );
+        }
+        for jump_host in ["bastion;id", "b:port", "x$(id)@b", "[::1]x", ""] {
+            let opts = SshOptions {
+                jump_host: Some(jump_host.into()),
+                ..Default::default()
+            };
+            assert!(
+                validate_ssh_options(&opts).is_err(),
+                "{:?} should be rejected",
+                jump_host
+            );
+        }
+
+        assert!(
+            validate_ssh_options(&opts(|o| o.wsl_distro = Some("Ubuntu-22.04".into()))).is_ok()
+        );
+        assert!(
+            validate_ssh_options(&opts(|o| o.wsl_distro = Some("Ubuntu & calc".into()))).is_err()
+        );
+    }
+
+    #[test]
+    fn terminal_command_rejects_unsafe_usernames() {
+        let env = HashMap::new();
+        let opts = SshOptions {
+            username: Some("rov".into()),
+            ..Default::default()
+        };
+        let line = terminal_shell_command("kyubic_main", "192.168.9.100", false, "", &env, &opts);
+        assert!(line.unwrap().ends_with(" rov@kyubic_main"));
+
+        // Preset usernames are merged into the options before this check runs
+        for username in ["x$(touch /tmp/p)", "a b", "`id`", "u;id"] {
+            let opts = SshOptions {
+                username: Some(username.into()),
+                ..Default::default()
+            };
+            let line =
+                terminal_shell_command("kyubic_main", "192.168.9.100", false, "", &env, &opts);
+            assert!(line.is_err(), "{:?} should be rejected", username);
+        }
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn ping_args_switch_to_ipv6_for_v6_and_zoned_targets() {
+        let opts = PingOptions::default();
+        let args = |target| ping_args(target, 1, 1000, &opts);
+
+        #[cfg(target_os = "windows")]
+        {
+            let expected = |target| strings(&["-n", "1", target, "-w", "1000"]);
+            assert_eq!(args("192.168.9.100"), ("ping", expected("192.168.9.100")));
+            for target in ["fe80::1", "fe80::1%12"] {
+                let mut v6 = strings(&["-6"]);
+                v6.extend(expected(target));
+                assert_eq!(args(target), ("ping", v6));
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let wait = if cfg!(target_os = "macos") {
+                "1000"
+            } else {
+                "1"
+            };
+            let expected = |target| strings(&["-c", "1", "-W", wait, target]);
+            assert_eq!(args("192.168.9.100"), ("ping", expected("192.168.9.100")));
+            // macOS ping6 takes no wait time at all
+            let expected_v6 = |target| {
+                if cfg!(target_os = "macos") {
+                    strings(&["-c", "1", target])
+                } else {
+                    expected(target)
+                }
+            };
+            assert_eq!(args("fe80::1"), ("ping6", expected_v6("fe80::1")));
+            // The zone is passed through untouched for link-local addresses
+            assert_eq!(args("fe80::1%eth0"), ("ping6", expected_v6("fe80::1%eth0")));
+        }
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("a; rm -rf /"), "'a; rm -rf /'");
+        assert_eq!(shell_quote("`id`"), "'`id`'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_quote_survives_sh_as_a_literal_argument() {
+        for arg in [
+            "it's",
+            "a; rm -rf /",
+            "`id`",
+            "$(id) $HOME",
+            "echo \"a & b\" | tee > %PATH% ^",
+        ] {
+            let output = Command::new("sh")
+                .args(["-c", &format!("printf %s {}", shell_quote(arg))])
+                .output()
+                .expect("sh should run");
+            assert_eq!(String::from_utf8_lossy(&output.stdout), arg);
+        }
+    }
+
+    #[test]
+    fn quote_for_shell_uses_argv_escaping_without_a_posix_shell() {
+        assert_eq!(quote_for_shell("it's", true), shell_quote("it's"));
+        assert_eq!(
+            quote_for_shell("echo \"a & b\"", false),
+            r#""echo \"a & b\"""#
+        );
+        assert_eq!(quote_for_shell("a; `id`", false), "\"a; `id`\"");
+        assert_eq!(
+            quote_if_needed("rov@192.168.9.100", false),
+            "rov@192.168.9.100"
+        );
+        assert_eq!(quote_if_needed("$(id)", true), "'$(id)'");
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn windows_terminal_runs_the_line_without_cmd() {
+        let line = r#"ssh -t rov@kyubic "echo \"a & b\"; ls | grep %PATH%""#;
+        let cmd = windows_terminal_command(line, WindowMode::Tab, &TerminalOptions::default());
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args[..3], strings(&["-w", "0", "new-tab"]));
+        assert!(!args.iter().any(|a| a == "cmd" || a == "/k"));
+        assert_eq!(
+            args.last().unwrap(),
+            r#"ssh -t rov@kyubic "echo \"a & b\"\; ls | grep %PATH%""#
+        );
+    }
+
+    #[test]
+    fn strip_ansi_cleans_a_captured_ros_log_line() {
+        // `ros2 launch` over `ssh -t` under `bash -i`: the prompt's OSC title, a
+        // coloured RCLCPP line, an erase-line progress update and CRLF endings.
+        let captured = "\x1b]0;rov@kyubic-main: ~\x07\x1b[33m[WARN] [1712041532.284611295] \
+                        [depth_driver]: depth sensor timeout (200 ms)\x1b[0m\r\n\
+                        \x1b[2K\r[INFO] [1712041532.301022817] [imu_node]: calibrated\r\n";
+        assert_eq!(
+            strip_ansi(captured),
+            "[WARN] [1712041532.284611295] [depth_driver]: depth sensor timeout (200 ms)\n\
+             [INFO] [1712041532.301022817] [imu_node]: calibrated\n"
+        );
+    }
+
+    #[test]
+    fn strip_ansi_handles_st_terminated_osc_and_private_modes() {
+        assert_eq!(
+            strip_ansi("\x1b]2;roslaunch\x1b\\\x1b[?25lok\x1b[?25h"),
+            "ok"
+        );
+    }
+
+    #[test]
+    fn reply_latency_tells_an_unparsed_reply_from_a_time() {
+        let reply = "64 bytes from 192.168.9.100: icmp_seq=1 ttl=64 time=0.412 ms";
+        assert_eq!(reply_latency("kyubic", reply).unwrap(), 0.412);
+        assert!(matches!(
+            reply_latency("kyubic", "1 packets transmitted, 1 received"),
+            Err(AppError::ParseFailed(_))
+        ));
+    }
+
+    #[test]
+    fn parse_ping_rtt_reads_localized_windows_output() {
+        for (output, rtt) in [
+            (
+                "64 bytes from 10.0.0.2: icmp_seq=1 ttl=64 time=12.3 ms",
+                12.3,
+            ),
+            ("Reply from 10.0.0.2: bytes=32 time=12ms TTL=64", 12.0),
+            ("Reply from 10.0.0.2: bytes=32 time<1ms TTL=64", 1.0),
+            ("10.0.0.2 からの応答: バイト数 =32 時間 =1ms TTL=64", 1.0),
+            ("10.0.0.2 からの応答: バイト数 =32 時間 <1ms TTL=64", 1.0),
+            ("Antwort von 10.0.0.2: Bytes=32 Zeit=3ms TTL=64", 3.0),
+        ] {
+            assert_eq!(parse_ping_rtt(output), Some(rtt), "{}", output);
+        }
+        assert_eq!(parse_ping_rtt("Request timed out."), None);
+    }
+
+    #[test]
+    fn multiplex_keys_the_socket_on_user_host_and_port() {
+        let opts = SshOptions {
+            multiplex: true,
+            ..SshOptions::default()
+        };
+        let control_path = ssh_option_args(&opts)
+            .into_iter()
+            .find(|arg| arg.starts_with("ControlPath="));
+
+        if cfg!(target_os = "windows") {
+            // Windows' OpenSSH has no ControlMaster; only WSL gets the options
+            assert_eq!(control_path, None);
+            let wsl = SshOptions { wsl: true, ..opts };
+            assert!(ssh_option_args(&wsl).contains(&"ControlPath=/tmp/kyubic-ssh-%C".to_string()));
+        } else {
+            let expected = std::env::temp_dir().join("kyubic-ssh-%C");
+            assert_eq!(
+                control_path,
+                Some(format!("ControlPath={}", expected.display()))
+            );
+        }
+    }
+
+    #[test]
+    fn validate_extra_ssh_opts_only_allows_listed_keys() {
+        let check = |args: &[&str]| validate_extra_ssh_opts(&strings(args));
+        assert!(check(&["-o", "Compression=yes", "-o", "serveraliveinterval=15"]).is_ok());
+        assert!(check(&["-o", "Ciphers=aes128-ctr,aes256-ctr", "-p", "2222"]).is_ok());
+        for option in [
+            "ProxyCommand=nc",
+            "LocalCommand=id",
+            "PKCS11Provider=/tmp/evil.so",
+            "SecurityKeyProvider=/tmp/evil.so",
+            "RemoteCommand=reboot",
+            "ProxyJump=attacker",
+            "Compression",
+        ] {
+            assert!(
+                check(&["-o", option]).is_err(),
+                "{} should be refused",
+                option
+            );
+        }
+        assert!(check(&["-o"]).is_err());
+        assert!(check(&["-F", "/tmp/config"]).is_err());
+    }
+
+    #[test]
+    fn parse_ping_statistics_reads_each_platforms_summary() {
+        let linux = "PING 192.168.9.100 (192.168.9.100) 56(84) bytes of data.\n\
+                     64 bytes from 192.168.9.100: icmp_seq=1 ttl=64 time=0.412 ms\n\
+                     \n\
+                     --- 192.168.9.100 ping statistics ---\n\
+                     4 packets transmitted, 3 received, 25% packet loss, time 3004ms\n\
+                     rtt min/avg/max/mdev = 0.398/0.421/0.447/0.020 ms\n";
+        let macos = "--- 192.168.9.100 ping statistics ---\n\
+                     4 packets transmitted, 4 packets received, 0.0% packet loss\n\
+                     round-trip min/avg/max/stddev = 1.201/1.734/2.950/0.704 ms\n";
+        let windows = "Reply from 192.168.9.100: bytes=32 time=2ms TTL=64\n\
+                       \n\
+                       Ping statistics for 192.168.9.100:\n\
+                       \x20   Packets: Sent = 4, Received = 4, Lost = 0 (0% loss),\n\
+                       Approximate round trip times in milli-seconds:\n\
+                       \x20   Minimum = 1ms, Maximum = 3ms, Average = 2ms\n";
+        let windows_ja = "192.168.9.100 からの応答: バイト数 =32 時間 =2ms TTL=64\n\
+                          \n\
+                          192.168.9.100 の ping 統計:\n\
+                          \x20   パケット数: 送信 = 4、受信 = 2、損失 = 2 (50% の損失)、\n\
+                          ラウンド トリップの概算時間 (ミリ秒):\n\
+                          \x20   最小 = 1ms、最大 = 3ms、平均 = 2ms\n";
+
+        for (output, sent, received, avg) in [
+            (linux, 4, 3, Some(0.421)),
+            (macos, 4, 4, Some(1.734)),
+            (windows, 4, 4, Some(2.0)),
+            (windows_ja, 4, 2, Some(2.0)),
+        ] {
+            let stats = parse_ping_statistics(output).expect(output);
+            assert_eq!((stats.sent, stats.received), (sent, received), "{}", output);
+            assert_eq!(stats.avg_rtt_ms, avg, "{}", output);
+        }
+        assert_eq!(
+            parse_ping_statistics(windows_ja).unwrap().loss_percent,
+            50.0
+        );
+    }
+
+    #[test]
+    fn parse_ping_statistics_handles_total_loss() {
+        let linux = "--- 192.168.9.100 ping statistics ---\n\
+                     4 packets transmitted, 0 received, 100% packet loss, time 3062ms\n";
+        let windows = "Request timed out.\n\
+                       \n\
+                       Ping statistics for 192.168.9.100:\n\
+                       \x20   Packets: Sent = 4, Received = 0, Lost = 4 (100% loss),\n";
+        let windows_ja = "要求がタイムアウトしました。\n\
+                          \n\
+                          192.168.9.100 の ping 統計:\n\
+                          \x20   パケット数: 送信 = 4、受信 = 0、損失 = 4 (100% の損失)、\n";
+        for output in [linux, windows, windows_ja] {
+            let stats = parse_ping_statistics(output).expect(output);
+            assert_eq!((stats.sent, stats.received), (4, 0), "{}", output);
+            assert_eq!(stats.loss_percent, 100.0);
+            assert_eq!(stats.avg_rtt_ms, None);
+        }
+        assert!(parse_ping_statistics("ping: unknown host kyubic").is_none());
+    }
+
+    #[test]
+    fn expand_home_resolves_a_leading_tilde() {
+        let home_var = if cfg!(target_os = "windows") {
+            "USERPROFILE"
+        } else {
+            "HOME"
+        };
+        let home = std::env::var_os(home_var).expect("home directory");
+        assert_eq!(
+            expand_home("~/.ssh/id_kyubic"),
+            Path::new(&home)
+                .join(".ssh/id_kyubic")
+                .display()
+                .to_string()
+        );
+        assert_eq!(expand_home("/etc/ssh/id_kyubic"), "/etc/ssh/id_kyubic");
+        assert_eq!(expand_home("~rov/.ssh/id"), "~rov/.ssh/id");
+    }
+
+    #[test]
+    fn host_latency_serializes_its_state_inline() {
+        let online = HostLatency {
+            state: PingState::Online,
+            latency_ms: Some(1.5),
+        };
+        assert_eq!(
+            serde_json::to_value(online).unwrap(),
+            serde_json::json!({ "state": "online", "latency_ms": 1.5 })
+        );
+        assert_eq!(
+            serde_json::to_value(HostLatency::without_time(PingState::Unknown)).unwrap(),
+            serde_json::json!({ "state": "unknown", "latency_ms": null })
+        );
+    }
+
+    #[test]
+    fn validate_terminal_options_caps_the_tab_delay() {
+        let delay = |tab_delay_ms| TerminalOptions {
+            tab_delay_ms,
+            ..TerminalOptions::default()
+        };
+        assert!(validate_terminal_options(&delay(None)).is_ok());
+        assert!(validate_terminal_options(&delay(Some(MAX_TAB_DELAY_MS))).is_ok());
+        assert!(matches!(
+            validate_terminal_options(&delay(Some(4500))),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_terminal_command_wraps_the_line_for_the_emulator() {
+        let term_opts = TerminalOptions {
+            title: Some("kyubic".into()),
+            ..TerminalOptions::default()
+        };
+        let cmd = linux_terminal_command(
+            TerminalEmulator::GnomeTerminal,
+            "ssh -t rov@kyubic",
+            WindowMode::Tab,
+            &term_opts,
+        );
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(cmd.get_program(), "gnome-terminal");
+        assert_eq!(
+            args,
+            strings(&[
+                "--tab",
+                "--title=kyubic",
+                "--",
+                "bash",
+                "-c",
+                "ssh -t rov@kyubic; exec bash"
+            ])
+        );
+        // The preview shows the same argv, including the AppImage env cleanup
+        let preview = format!("{:?}", cmd);
+        assert!(
+            preview.contains("\"gnome-terminal\" \"--tab\""),
+            "{}",
+            preview
+        );
+        assert!(preview.contains("PYTHONHOME"), "{}", preview);
+    }
+
+    fn ssh_run(code: i32, stderr: &str) -> Output {
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(code << 8);
+        #[cfg(windows)]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(code as u32);
+        Output {
+            status,
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn ssh_error_separates_connection_failures_from_remote_ones() {
+        let kind = |code, stderr| AppError::ssh("ctx".to_string(), &ssh_run(code, stderr));
+        for stderr in [
+            "ssh: connect to host kyubic port 22: Connection refused",
+            "ssh: connect to host 192.168.9.100 port 22: No route to host",
+            "ssh: Could not resolve hostname kyubic: Name or service not known",
+        ] {
+            assert!(
+                matches!(kind(255, stderr), AppError::Unreachable(_)),
+                "{}",
+                stderr
+            );
+        }
+        assert!(matches!(
+            kind(
+                255,
+                "ssh: connect to host kyubic port 22: Connection timed out"
+            ),
+            AppError::Timeout(_)
+        ));
+        assert!(matches!(
+            kind(1, "tail: cannot open 'x' for reading"),
+            AppError::SshFailed(_)
+        ));
+    }
+
+    #[test]
+    fn build_ssh_command_passes_the_port_only_when_set() {
+        let with_port = SshOptions {
+            port: Some(2222),
+            username: Some("rov".into()),
+            ..SshOptions::default()
+        };
+        let line = build_ssh_command("kyubic", &with_port, Some("ls -la"));
+        assert!(line.starts_with("ssh -t -p 2222 "), "{}", line);
+        let quoted = quote_for_shell("ls -la", !cfg!(target_os = "windows"));
+        assert!(
+            line.ends_with(&format!(" rov@kyubic {}", quoted)),
+            "{}",
+            line
+        );
+
+        let line = build_ssh_command("kyubic", &SshOptions::default(), None);
+        assert!(line.starts_with("ssh -o ConnectTimeout="), "{}", line);
+        assert!(!line.contains("-p "), "{}", line);
+        assert!(line.ends_with(" kyubic"), "{}", line);
+    }
+
+    #[test]
+    fn env_exports_quotes_values_and_sorts_keys() {
+        let env = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert_eq!(env_exports(&env(&[])).unwrap(), "");
+        assert_eq!(
+            env_exports(&env(&[("ROS_DOMAIN_ID", "7"), ("MSG", "it's; $(id)")])).unwrap(),
+            r"export MSG='it'\''s; $(id)' ROS_DOMAIN_ID='7'; "
+        );
+        assert!(env_exports(&env(&[("_X1", "ok")])).is_ok());
+    }
+
+    #[test]
+    fn env_exports_rejects_invalid_keys() {
+        for key in ["", "1ROS", "ros_domain", "A-B", "A B", "X;id", "PATH=/tmp"] {
+            let env = HashMap::from([(key.to_string(), "v".to_string())]);
+            assert!(
+                matches!(env_exports(&env), Err(AppError::InvalidInput(_))),
+                "{:?} should be rejected",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn parse_df_output_keeps_real_mounts() {
+        let output = "\
+Mounted on 1B-blocks Used Avail
+/ 1000 400 600
+/media/rov/USB DRIVE 2000 500 1500
+/dev 10 0 10
+/dev/shm 10 0 10
+/run/user/1000 10 0 10
+/snap/core/1 10 10 0
+/devices 30 10 20
+/mnt/empty 0 0 0
+/broken 1 2
+/garbage x y z
+";
+        let disks: Vec<(String, u64, u64, u64)> = parse_df_output(output)
+            .into_iter()
+            .map(|d| (d.mount, d.total_bytes, d.used_bytes, d.available_bytes))
+            .collect();
+        assert_eq!(
+            disks,
+            vec![
+                ("/".to_string(), 1000, 400, 600),
+                ("/media/rov/USB DRIVE".to_string(), 2000, 500, 1500),
+                ("/devices".to_string(), 30, 10, 20),
+            ]
+        );
+        assert!(parse_df_output("").is_empty());
+    }
+
+    #[test]
+    fn parse_vitals_reads_loadavg_and_meminfo() {
+        let output = format!(
+            "0.52 0.41 0.30 2/345 6789\n{}\nMemTotal:        8000000 kB\nMemFree:          1000000 kB\nMemAvailable:    5000000 kB\n",
+            VITALS_SENTINEL
+        );
+        let vitals = parse_vitals(&output).unwrap();
+        assert_eq!(
+            (vitals.load_1m, vitals.load_5m, vitals.load_15m),
+            (0.52, 0.41, 0.30)
+        );
+        assert_eq!(vitals.mem_total_kb, 8000000);
+        assert_eq!(vitals.mem_available_kb, 5000000);
+    }
+
+    #[test]
+    fn parse_vitals_rejects_incomplete_output() {
+        let cases = [
+            "0.52 0.41 0.30 2/345 6789\nMemTotal: 8000000 kB\n".to_string(),
+            format!(
+                "0.52 oops\n{}\nMemTotal: 8000000 kB\nMemAvailable: 5000000 kB\n",
+                VITALS_SENTINEL
+            ),
+            format!(
+                "0.52 0.41 0.30\n{}\nMemTotal: 8000000 kB\n",
+                VITALS_SENTINEL
+            ),
+        ];
+        for output in &cases {
+            assert!(
+                matches!(parse_vitals(output), Err(AppError::ParseFailed(_))),
+                "{:?} should not parse",
+                output
+            );
+        }
+    }
+
+    #[test]
+    fn parse_cidr_masks_network_and_excludes_broadcast() {
+        let (network, prefix, hosts) = parse_cidr(" 192.168.1.77/24\n").unwrap();
+        assert_eq!(network, Ipv4Addr::new(192, 168, 1, 0));
+        assert_eq!(prefix, 24);
+        let hosts: Vec<Ipv4Addr> = hosts.map(Ipv4Addr::from).collect();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts.first(), Some(&Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(hosts.last(), Some(&Ipv4Addr::new(192, 168, 1, 254)));
+
+        assert_eq!(expand_cidr("10.0.0.0/16").unwrap().len(), 65534);
+    }
+
+    #[test]
+    fn parse_cidr_keeps_every_address_in_31_and_32() {
+        assert_eq!(
+            expand_cidr("10.0.0.5/31").unwrap(),
+            vec![Ipv4Addr::new(10, 0, 0, 4), Ipv4Addr::new(10, 0, 0, 5)]
+        );
+        assert_eq!(
+            expand_cidr("10.0.0.5/32").unwrap(),
+            vec![Ipv4Addr::new(10, 0, 0, 5)]
+        );
+    }
+
+    #[test]
+    fn parse_cidr_rejects_invalid_and_wide_ranges() {
+        for cidr in [
+            "",
+            "10.0.0.0",
+            "10.0.0/24",
+            "10.0.0.0/x",
+            "10.0.0.0/33",
+            "10.0.0.0/15",
+            "10.0.0.0/0",
+        ] {
+            assert!(
+                matches!(parse_cidr(cidr), Err(AppError::InvalidInput(_))),
+                "{:?} should be rejected",
+                cidr
+            );
+        }
+    }
 }
@@ -50,6 +50,16 @@ function App() {
     return () => clearInterval(intervalId);
   }, []);
 
+  useEffect(() => {
+    // Warn once at startup if ssh, ping or the terminal launcher is missing
+    invoke("check_local_prerequisites").then((prereqs) => {
+      const missing = prereqs.filter((p) => !p.available).map((p) => p.name);
+      if (missing.length > 0) {
+        alert(`Missing local tools: ${missing.join(", ")}`);
+      }
+    });
+  }, []);
+
   // --- Helpers & Handlers ---
 
   // Ping all devices to update status
@@ -61,8 +71,9 @@ function App() {
       }
 
       try {
-        const isOnline = await invoke("check_connection_status", { target: device.ip });
-        setDeviceStatus((prev) => ({ ...prev, [device.ip]: isOnline }));
+        // Retry dropped echoes so one lost packet over Wi-Fi doesn't flip the dot
+        const { state } = await invoke("check_connection_status", { target: device.ip, retries: 2 });
+        setDeviceStatus((prev) => ({ ...prev, [device.ip]: state === "online" }));
       } catch (error) {
         setDeviceStatus((prev) => ({ ...prev, [device.ip]: false }));
       }
@@ -80,7 +91,7 @@ function App() {
         remoteCommand: useRos ? commandStr : "",
       });
     } catch (e) {
-      alert(`Terminal Launch Error: ${e}`);
+      alert(`Terminal Launch Error: ${e.message ?? e}`);
     }
   };
 
@@ -92,7 +103,7 @@ function App() {
       await invoke("exec_shutdown_command", { hostname: shutdownTarget.name });
       setShutdownTarget(null); // Close modal
     } catch (e) {
-      alert(`Shutdown Error: ${e}`);
+      alert(`Shutdown Error: ${e.message ?? e}`);
     }
   };
 